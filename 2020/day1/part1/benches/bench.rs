@@ -0,0 +1,107 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day1_part1::bitset::BitReport;
+use day1_part1::multiset;
+use std::collections::HashSet;
+
+/// The HashSet search the old part 2 used, kept as a baseline.
+fn two_num_sum_product(sum: i32, report: &[i32]) -> Option<i32> {
+    let mut visited = HashSet::new();
+    report
+        .iter()
+        .filter_map(|&entry| {
+            if entry < sum / 2 {
+                Some(sum - entry)
+            } else if entry > sum {
+                None
+            } else {
+                Some(entry)
+            }
+        })
+        .find(|&entry| !visited.insert(entry))
+        .map(|addend| addend * (sum - addend))
+}
+
+/// The old part 2 loop around [two_num_sum_product].
+fn three_num_sum_product(sum: i32, report: &[i32]) -> Option<i32> {
+    report
+        .iter()
+        .find_map(|&entry| two_num_sum_product(sum - entry, report).map(|product| entry * product))
+}
+
+/// Returns `len` distinct entries, and a target only one pair of them sums to. The entries are
+/// scattered below the target, about a quarter as dense as they could be.
+///
+/// Every entry is 1 more than a multiple of 4, so any two sum to 2 more than a multiple of 4,
+/// except for the pair at the end, which makes every search look at the whole report.
+/// The last entry, 1, makes the only triple summing to 1 more than the target, with that pair.
+/// The entries have to be distinct, or the HashSet search takes the first repeat as a match.
+fn synthetic(len: usize) -> (Vec<i64>, i64) {
+    let spread = len.next_power_of_two() as u64;
+    let target = 4 * spread as i64;
+    let mut report = (1..len as u64 - 2)
+        // odd multipliers permute the integers mod a power of 2
+        .map(|i| (i.wrapping_mul(0x9E37_79B1) & (spread - 1)) as i64 * 4 + 1)
+        .collect::<Vec<_>>();
+    report.extend([target / 4, target - target / 4, 1].iter());
+    (report, target)
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let reports = [("10k", synthetic(10_000)), ("1M", synthetic(1_000_000))];
+
+    let mut group = c.benchmark_group("pairs");
+    for (name, (report, target)) in &reports {
+        group.throughput(Throughput::Elements(report.len() as u64));
+        let narrow = report.iter().map(|&e| e as i32).collect::<Vec<_>>();
+        let bits = BitReport::new(report).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("hashset", name), &narrow, |b, report| {
+            b.iter(|| two_num_sum_product(black_box(*target as i32), black_box(report)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("multiset", name), report, |b, report| {
+            b.iter(|| multiset::all_pairs(black_box(report), black_box(*target)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("bitset", name), report, |b, report| {
+            b.iter(|| {
+                BitReport::new(black_box(report))
+                    .unwrap()
+                    .pairs(black_box(*target))
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("bitset_query", name), &bits, |b, bits| {
+            b.iter(|| bits.pairs(black_box(*target)))
+        });
+    }
+    group.finish();
+
+    // the old triple search builds a HashSet for every entry, so keep these small
+    let mut group = c.benchmark_group("triples");
+    group.sample_size(10);
+    for (name, (report, target)) in &[("1k", synthetic(1_000)), ("4k", synthetic(4_000))] {
+        group.throughput(Throughput::Elements(report.len() as u64));
+        let narrow = report.iter().map(|&e| e as i32).collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("hashset", name), &narrow, |b, report| {
+            b.iter(|| three_num_sum_product(black_box(*target as i32 + 1), black_box(report)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("multiset", name), report, |b, report| {
+            b.iter(|| multiset::all_triples(black_box(report), black_box(target + 1)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("bitset", name), report, |b, report| {
+            b.iter(|| {
+                BitReport::new(black_box(report))
+                    .unwrap()
+                    .triples(black_box(target + 1))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
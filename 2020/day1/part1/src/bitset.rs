@@ -0,0 +1,231 @@
+//! Pair and triple search over a bitset indexed by entry, for reports of small non-negative
+//! entries.
+//!
+//! A pair summing to `t` is an `a` where both `a` and `t - a` are set. Keeping a reversed
+//! copy of the bitset, where bit `j` is entry `max - j`, that's bit `a` of the bitset and
+//! bit `a + max - t` of the reverse, so a whole word of candidates is one shift and one AND.
+//!
+//! Matches follow the same rules as [crate::multiset], and come out in the same order.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The largest entry a [BitReport] takes, which keeps each bitset to at most 2 MiB.
+pub const MAX_ENTRY: i64 = (1 << 24) - 1;
+
+const WORD: usize = u64::BITS as usize;
+
+/// The entries of a report, as bitsets of the entries seen at least once, twice and thrice.
+#[derive(Clone, Debug)]
+pub struct BitReport {
+    max: usize,
+    once: Vec<u64>,
+    twice: Vec<u64>,
+    thrice: Vec<u64>,
+    /// `once` back to front, so bit `j` is entry `max - j`.
+    reversed: Vec<u64>,
+}
+
+impl BitReport {
+    pub fn new(report: &[i64]) -> Result<Self, BitsetError> {
+        let max = report.iter().try_fold(0, |max, &entry| match entry {
+            e if e < 0 => Err(BitsetError::Negative(e)),
+            e if e > MAX_ENTRY => Err(BitsetError::TooLarge(e)),
+            e => Ok(max.max(e as usize)),
+        })?;
+
+        let words = max / WORD + 1;
+        let mut bits = BitReport {
+            max,
+            once: vec![0; words],
+            twice: vec![0; words],
+            thrice: vec![0; words],
+            reversed: vec![0; words],
+        };
+        for &entry in report {
+            let entry = entry as usize;
+            let (word, bit) = (entry / WORD, 1 << (entry % WORD));
+            bits.thrice[word] |= bits.twice[word] & bit;
+            bits.twice[word] |= bits.once[word] & bit;
+            bits.once[word] |= bit;
+
+            let j = max - entry;
+            bits.reversed[j / WORD] |= 1 << (j % WORD);
+        }
+        Ok(bits)
+    }
+
+    /// How many times `entry` is in the report, up to 3.
+    pub fn count(&self, entry: i64) -> usize {
+        if entry < 0 || entry as usize > self.max {
+            return 0;
+        }
+        let entry = entry as usize;
+        [&self.once, &self.twice, &self.thrice]
+            .iter()
+            .filter(|bits| bits[entry / WORD] & (1 << (entry % WORD)) != 0)
+            .count()
+    }
+
+    /// Every pair of entries that sums to `target`.
+    pub fn pairs(&self, target: i64) -> Vec<[i64; 2]> {
+        let mut pairs = Vec::new();
+        self.for_each_pair(target, |a, b| pairs.push([a, b]));
+        pairs
+    }
+
+    /// Every triple of entries that sums to `target`.
+    pub fn triples(&self, target: i64) -> Vec<[i64; 3]> {
+        let mut triples = Vec::new();
+        for a in ones(&self.once).map(|a| a as i64) {
+            if 3 * a > target {
+                break;
+            }
+            self.for_each_pair(target - a, |b, c| {
+                let triple = [a, b, c];
+                let needed = |entry| triple.iter().filter(|&&e| e == entry).count();
+                if b >= a && triple.iter().all(|&e| self.count(e) >= needed(e)) {
+                    triples.push(triple);
+                }
+            });
+        }
+        triples
+    }
+
+    /// Calls `f` with every pair `a <= b` of entries summing to `target`, in ascending order
+    /// of `a`.
+    fn for_each_pair(&self, target: i64, mut f: impl FnMut(i64, i64)) {
+        if target < 0 {
+            return;
+        }
+        // a + max - target, for the first a of each word
+        let offset = self.max as i64 - target;
+        // only a <= target / 2 need looking at, the rest are the other half of a pair
+        let last_word = ((target / 2) as usize / WORD).min(self.once.len() - 1);
+
+        for w in 0..=last_word {
+            let reversed = bits_from(&self.reversed, (w * WORD) as i64 + offset);
+            let mut matches = self.once[w] & reversed;
+            while matches != 0 {
+                let a = (w * WORD) as i64 + i64::from(matches.trailing_zeros());
+                matches &= matches - 1;
+
+                // can't overflow: target isn't negative, and a is at most MAX_ENTRY
+                let b = target - a;
+                if a < b || (a == b && self.count(a) >= 2) {
+                    f(a, b);
+                } else if a > b {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The 64 bits of `bits` starting at bit `start`, with anything out of range as zeroes.
+#[inline]
+fn bits_from(bits: &[u64], start: i64) -> u64 {
+    let word = |i: i64| match usize::try_from(i) {
+        Ok(i) if i < bits.len() => bits[i],
+        _ => 0,
+    };
+    let (w, shift) = (start.div_euclid(WORD as i64), start.rem_euclid(WORD as i64));
+    if shift == 0 {
+        word(w)
+    } else {
+        (word(w) >> shift) | (word(w + 1) << (WORD as i64 - shift))
+    }
+}
+
+/// The positions of the set bits, in ascending order.
+fn ones(bits: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    bits.iter().enumerate().flat_map(|(w, &word)| {
+        let mut word = word;
+        std::iter::from_fn(move || {
+            let bit = word.trailing_zeros() as usize;
+            word &= word.wrapping_sub(1);
+            (bit < WORD).then_some(w * WORD + bit)
+        })
+    })
+}
+
+/// Why a report can't be made into a [BitReport].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BitsetError {
+    Negative(i64),
+    /// Larger than [MAX_ENTRY].
+    TooLarge(i64),
+}
+
+impl fmt::Display for BitsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitsetError::Negative(e) => write!(f, "entry {} is negative", e),
+            BitsetError::TooLarge(e) => {
+                write!(
+                    f,
+                    "entry {} is larger than the largest supported, {}",
+                    e, MAX_ENTRY
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BitsetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multiset;
+
+    #[test]
+    fn matches_multiset() {
+        let example = vec![1721, 979, 366, 299, 675, 1456, 1000, 1020, 1020, 5, 15, 985];
+        let scattered = (0..300).map(|i: i64| (i * 7919) % 1500).collect::<Vec<_>>();
+        let reports = [
+            example,
+            scattered,
+            vec![1010, 5],
+            vec![1010, 5, 1010],
+            vec![673, 673, 674],
+            vec![673, 673, 673],
+            vec![0, 0, 0, 63, 64, 65, 127, 128],
+            vec![0],
+        ];
+
+        for report in &reports {
+            let bits = BitReport::new(report).unwrap();
+            for target in (-3..3100)
+                .step_by(29)
+                .chain([0, 128, 2019, 2020].iter().copied())
+            {
+                assert_eq!(bits.pairs(target), multiset::all_pairs(report, target));
+                assert_eq!(bits.triples(target), multiset::all_triples(report, target));
+            }
+        }
+    }
+
+    #[test]
+    fn counts() {
+        let bits = BitReport::new(&[3, 64, 3, 64, 64, 64]).unwrap();
+        assert_eq!(bits.count(3), 2);
+        assert_eq!(bits.count(64), 3);
+        assert_eq!(bits.count(4), 0);
+        assert_eq!(bits.count(-1), 0);
+        assert_eq!(bits.count(1000), 0);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            BitReport::new(&[1, -2]).unwrap_err(),
+            BitsetError::Negative(-2)
+        );
+        assert_eq!(
+            BitReport::new(&[MAX_ENTRY + 1]).unwrap_err(),
+            BitsetError::TooLarge(MAX_ENTRY + 1)
+        );
+        assert!(BitReport::new(&[]).unwrap().pairs(0).is_empty());
+    }
+}
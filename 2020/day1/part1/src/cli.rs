@@ -0,0 +1,127 @@
+//! The command line both parts share, which differ only in how many entries they look for.
+
+use crate::{all_k_sums, product};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// The puzzle's target.
+pub const TARGET: i64 = 2020;
+
+/// What to look for, given as arguments: the target and number of entries, in that order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Args {
+    pub target: i64,
+    pub k: usize,
+}
+
+impl Args {
+    /// Parses `args`, filling in [TARGET] and `default_k` for any that are left out.
+    pub fn parse(
+        mut args: impl Iterator<Item = String>,
+        default_k: usize,
+    ) -> Result<Self, ArgError> {
+        let target = match args.next() {
+            Some(target) => target.parse().map_err(|_| ArgError::Target(target))?,
+            None => TARGET,
+        };
+        let k = match args.next() {
+            Some(k) => k.parse().map_err(|_| ArgError::K(k))?,
+            None => default_k,
+        };
+        Ok(Args { target, k })
+    }
+}
+
+/// An argument which isn't a number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgError {
+    Target(String),
+    K(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::Target(target) => write!(f, "target {:?} is not a number", target),
+            ArgError::K(k) => write!(f, "number of entries {:?} is not a number", k),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// The entries of a report, skipping any lines which aren't numbers.
+pub fn entries(input: &str) -> Vec<i64> {
+    input.lines().filter_map(|s| s.parse().ok()).collect()
+}
+
+/// Writes every match as a sum, followed by its product.
+pub fn write_matches<W: Write>(mut out: W, report: &[i64], args: Args) -> io::Result<()> {
+    let Args { target, k } = args;
+    let matches = all_k_sums(report, k, target);
+    if matches.is_empty() {
+        writeln!(out, "could not find {} numbers that add to {}", k, target)?;
+    }
+    for entries in matches {
+        let sum = entries
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect::<Vec<_>>();
+        writeln!(out, "{} = {}", sum.join(" + "), target)?;
+        match product(&entries) {
+            Some(product) => writeln!(out, "{}", product)?,
+            None => writeln!(out, "the product overflows")?,
+        }
+    }
+    Ok(())
+}
+
+/// Reads a report from stdin and prints every match, using `default_k` entries unless the
+/// arguments say otherwise. Bad arguments are printed, and exit with status 1.
+pub fn run(default_k: usize) -> io::Result<()> {
+    let args = Args::parse(std::env::args().skip(1), default_k).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    });
+
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer)?;
+
+    write_matches(io::stdout().lock(), &entries(&buffer), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, ArgError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), 3)
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(args(&[]), Ok(Args { target: 2020, k: 3 }));
+        assert_eq!(args(&["-5"]), Ok(Args { target: -5, k: 3 }));
+        assert_eq!(args(&["10", "4"]), Ok(Args { target: 10, k: 4 }));
+        assert_eq!(args(&["ten"]), Err(ArgError::Target("ten".to_string())));
+        assert_eq!(args(&["10", "-1"]), Err(ArgError::K("-1".to_string())));
+    }
+
+    #[test]
+    fn output() {
+        let report = entries("1721\n979\n366\n299\n675\n1456\nnot a number\n");
+        let mut out = Vec::new();
+        write_matches(&mut out, &report, Args { target: 2020, k: 2 }).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "299 + 1721 = 2020\n514579\n"
+        );
+
+        let mut out = Vec::new();
+        write_matches(&mut out, &report, Args { target: 1, k: 2 }).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "could not find 2 numbers that add to 1\n"
+        );
+    }
+}
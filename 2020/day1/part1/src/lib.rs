@@ -0,0 +1,257 @@
+//! Finding entries of an expense report that add up to a target.
+//!
+//! Each entry can be used once, though equal entries on different lines count separately.
+//! Whatever the method, the entries found are returned in ascending order.
+//! To find every match rather than the first, see [multiset], or [bitset] for reports of
+//! small non-negative entries.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+pub mod bitset;
+pub mod cli;
+pub mod multiset;
+
+/// Finds `k` entries that sum to `target`, picking the method by `k`.
+pub fn k_sum(report: &[i64], k: usize, target: i64) -> Option<Vec<i64>> {
+    match k {
+        0 => (target == 0).then_some(Vec::new()),
+        1 => report
+            .iter()
+            .find(|&&entry| entry == target)
+            .map(|&entry| vec![entry]),
+        2 => {
+            let mut sorted = report.to_vec();
+            sorted.sort_unstable();
+            two_sum(&sorted, target).map(|pair| pair.to_vec())
+        }
+        3 => three_sum(report, target).map(|triple| triple.to_vec()),
+        _ => meet_in_the_middle(report, k, target),
+    }
+}
+
+/// Like [k_sum], but finds every match when `k` is 2 or 3. See [multiset] for how.
+pub fn all_k_sums(report: &[i64], k: usize, target: i64) -> Vec<Vec<i64>> {
+    match k {
+        2 => multiset::all_pairs(report, target)
+            .iter()
+            .map(|pair| pair.to_vec())
+            .collect(),
+        3 => multiset::all_triples(report, target)
+            .iter()
+            .map(|triple| triple.to_vec())
+            .collect(),
+        _ => k_sum(report, k, target).into_iter().collect(),
+    }
+}
+
+/// Finds two entries that sum to `target`, walking in from both ends of a `sorted` report.
+/// O(n).
+pub fn two_sum(sorted: &[i64], target: i64) -> Option<[i64; 2]> {
+    let (mut lo, mut hi) = (0, sorted.len().checked_sub(1)?);
+    let target = i128::from(target);
+    while lo < hi {
+        // two i64s always add up within an i128
+        let sum = i128::from(sorted[lo]) + i128::from(sorted[hi]);
+        if sum == target {
+            return Some([sorted[lo], sorted[hi]]);
+        } else if sum < target {
+            lo += 1;
+        } else {
+            hi -= 1;
+        }
+    }
+    None
+}
+
+/// Finds three entries that sum to `target`. For each first entry, the rest of the report
+/// is a two-sum, solved by remembering the entries seen so far. O(n²).
+pub fn three_sum(report: &[i64], target: i64) -> Option<[i64; 3]> {
+    for (i, &a) in report.iter().enumerate() {
+        let mut seen = HashSet::new();
+        for &b in &report[i + 1..] {
+            // a `c` too big or small for an i64 can't be in the report
+            let c = i128::from(target) - i128::from(a) - i128::from(b);
+            if let Some(c) = i64::try_from(c).ok().filter(|c| seen.contains(c)) {
+                let mut triple = [a, b, c];
+                triple.sort_unstable();
+                return Some(triple);
+            }
+            seen.insert(b);
+        }
+    }
+    None
+}
+
+/// Finds `k` entries that sum to `target`, by splitting them into a lower half of `k / 2`
+/// entries and an upper half of the rest.
+///
+/// Every upper half is remembered by its sum, keeping only the one which starts latest in
+/// the report, as that's the one most likely to come after a given lower half. Then every
+/// lower half looks up the upper half it needs. O(n^⌈k/2⌉) time and memory, rather than the
+/// O(n^k) of trying everything.
+///
+/// Sums are taken as [i128]s, which can't overflow for any `k` that would finish.
+pub fn meet_in_the_middle(report: &[i64], k: usize, target: i64) -> Option<Vec<i64>> {
+    let (lower_k, upper_k) = (k / 2, k - k / 2);
+    let sum = |indices: &[usize]| indices.iter().map(|&i| i128::from(report[i])).sum::<i128>();
+
+    let mut uppers: HashMap<i128, Vec<usize>> = HashMap::new();
+    for_each_combination(report.len(), upper_k, |upper| {
+        let sum = sum(upper);
+        // combinations come in lexicographic order, so a later one starts at least as late
+        uppers.insert(sum, upper.to_vec());
+        true
+    });
+
+    let mut found = None;
+    for_each_combination(report.len(), lower_k, |lower| {
+        let after = lower.last().map_or(0, |&i| i + 1);
+        match uppers.get(&(i128::from(target) - sum(lower))) {
+            Some(upper) if upper.first().is_none_or(|&i| i >= after) => {
+                found = Some(lower.iter().chain(upper).map(|&i| report[i]).collect());
+                false
+            }
+            _ => true,
+        }
+    });
+
+    found.map(|mut entries: Vec<i64>| {
+        entries.sort_unstable();
+        entries
+    })
+}
+
+/// Calls `f` with every combination of `k` indices below `n`, each in ascending order,
+/// and the combinations in lexicographic order. Stops early if `f` returns `false`.
+pub fn for_each_combination(n: usize, k: usize, mut f: impl FnMut(&[usize]) -> bool) {
+    if k > n {
+        return;
+    }
+    let mut indices = (0..k).collect::<Vec<_>>();
+    loop {
+        if !f(&indices) {
+            return;
+        }
+        // bump the last index which has room to move, and pack the rest in behind it
+        let bump = match (0..k).rev().find(|&i| indices[i] < n - k + i) {
+            Some(bump) => bump,
+            None => return,
+        };
+        indices[bump] += 1;
+        for i in bump + 1..k {
+            indices[i] = indices[i - 1] + 1;
+        }
+    }
+}
+
+/// The product of `entries`, unless it overflows.
+pub fn product(entries: &[i64]) -> Option<i128> {
+    entries
+        .iter()
+        .try_fold(1i128, |product, &entry| product.checked_mul(entry.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    /// Tries every combination.
+    fn brute_force(report: &[i64], k: usize, target: i64) -> bool {
+        let mut found = false;
+        for_each_combination(report.len(), k, |c| {
+            found = c.iter().map(|&i| i128::from(report[i])).sum::<i128>() == i128::from(target);
+            !found
+        });
+        found
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(k_sum(&EXAMPLE, 2, 2020), Some(vec![299, 1721]));
+        assert_eq!(k_sum(&EXAMPLE, 3, 2020), Some(vec![366, 675, 979]));
+        assert_eq!(product(&[299, 1721]), Some(514579));
+        assert_eq!(product(&[366, 675, 979]), Some(241861950));
+        assert_eq!(meet_in_the_middle(&EXAMPLE, 2, 2020), Some(vec![299, 1721]));
+        assert_eq!(
+            meet_in_the_middle(&EXAMPLE, 3, 2020),
+            Some(vec![366, 675, 979])
+        );
+        assert_eq!(meet_in_the_middle(&EXAMPLE, 0, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn entries_used_once() {
+        assert_eq!(k_sum(&[1010, 5], 2, 2020), None);
+        assert_eq!(k_sum(&[1010, 5, 1010], 2, 2020), Some(vec![1010, 1010]));
+        assert_eq!(k_sum(&[700, 620], 3, 2020), None);
+        assert_eq!(k_sum(&[5, 5, 5, 5], 4, 20), Some(vec![5; 4]));
+        assert_eq!(k_sum(&[5, 5, 5], 4, 20), None);
+    }
+
+    #[test]
+    fn any_k() {
+        let report = (0..30)
+            .map(|i: i64| (i * 7919) % 1000 - 200)
+            .collect::<Vec<_>>();
+
+        for k in 0..=5 {
+            for target in (-600..3000).step_by(137) {
+                let found = k_sum(&report, k, target);
+                assert_eq!(found.is_some(), brute_force(&report, k, target));
+
+                if let Some(entries) = found {
+                    assert_eq!(entries.len(), k);
+                    assert_eq!(entries.iter().sum::<i64>(), target);
+                    assert!(entries.windows(2).all(|w| w[0] <= w[1]));
+                    // every entry found really is in the report, as often as it's used
+                    let mut left = report.clone();
+                    for entry in entries {
+                        let i = left.iter().position(|&e| e == entry).unwrap();
+                        left.swap_remove(i);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_overflow() {
+        const BIG: i64 = 9_000_000_000_000_000_000;
+        let report = [BIG, BIG, i64::MAX, i64::MIN, -BIG, 1];
+        for k in 0..=5 {
+            for &target in &[2020, 0, -1, 1, BIG, -BIG, i64::MAX, i64::MIN] {
+                let found = k_sum(&report, k, target);
+                assert_eq!(found.is_some(), brute_force(&report, k, target));
+                if let Some(entries) = found {
+                    let sum = entries.iter().map(|&e| i128::from(e)).sum::<i128>();
+                    assert_eq!(sum, i128::from(target));
+                }
+            }
+        }
+        assert_eq!(k_sum(&report, 2, 0), Some(vec![-BIG, BIG]));
+        assert_eq!(k_sum(&report, 3, 1), Some(vec![-BIG, 1, BIG]));
+        assert_eq!(k_sum(&report, 3, i64::MAX), Some(vec![-BIG, BIG, i64::MAX]));
+        assert_eq!(k_sum(&[BIG, BIG], 4, 2020), None);
+    }
+
+    #[test]
+    fn combinations() {
+        let mut all = Vec::new();
+        for_each_combination(4, 2, |c| {
+            all.push(c.to_vec());
+            true
+        });
+        assert_eq!(all, [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]);
+
+        let mut count = 0;
+        for_each_combination(3, 0, |_| {
+            count += 1;
+            true
+        });
+        assert_eq!(count, 1);
+        for_each_combination(2, 3, |_| panic!("no combinations"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Every pair or triple of entries that sums to a target, rather than just the first.
+//!
+//! The report is treated as a multiset: an entry can only be used as often as it appears, so
+//! `1010 + 1010` only counts if 1010 is in the report twice. Matches are distinct by value,
+//! each in ascending order, and listed in ascending order.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// How many times each entry appears, in ascending order of entry.
+fn counts(report: &[i64]) -> BTreeMap<i64, usize> {
+    let mut counts = BTreeMap::new();
+    for &entry in report {
+        *counts.entry(entry).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Every pair of entries that sums to `target`.
+pub fn all_pairs(report: &[i64], target: i64) -> Vec<[i64; 2]> {
+    let counts = counts(report);
+    counts
+        .iter()
+        .filter_map(|(&a, &a_count)| {
+            // a `b` too big or small for an i64 can't be in the report
+            let b = target.checked_sub(a)?;
+            let enough = match a.cmp(&b) {
+                Ordering::Less => counts.contains_key(&b),
+                Ordering::Equal => a_count >= 2,
+                Ordering::Greater => false,
+            };
+            enough.then_some([a, b])
+        })
+        .collect()
+}
+
+/// Every triple of entries that sums to `target`. O(d²) for d distinct entries.
+pub fn all_triples(report: &[i64], target: i64) -> Vec<[i64; 3]> {
+    let counts = counts(report);
+    let entries = counts.keys().copied().collect::<Vec<_>>();
+
+    let mut triples = Vec::new();
+    for (i, &a) in entries.iter().enumerate() {
+        for &b in &entries[i..] {
+            // taken as an i128, as only the whole sum has to fit in an i64
+            let c = i128::from(target) - i128::from(a) - i128::from(b);
+            // later b only make c smaller
+            if c < i128::from(b) {
+                break;
+            }
+            // too big to be an entry, skip to a larger b
+            let c = match i64::try_from(c) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            let triple = [a, b, c];
+            let needed = |entry| triple.iter().filter(|&&e| e == entry).count();
+            let enough = triple.iter().all(|&entry| {
+                counts
+                    .get(&entry)
+                    .is_some_and(|&count| count >= needed(entry))
+            });
+            if enough {
+                triples.push(triple);
+            }
+        }
+    }
+    triples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates() {
+        // two equal entries below half the target are not a match
+        assert!(all_pairs(&[300, 300, 5], 2020).is_empty());
+        // half the target only matches itself if it's there twice
+        assert!(all_pairs(&[1010, 5], 2020).is_empty());
+        assert_eq!(all_pairs(&[1010, 5, 1010], 2020), [[1010, 1010]]);
+        assert_eq!(all_pairs(&[1010, 1010, 1010], 2020), [[1010, 1010]]);
+
+        assert!(all_triples(&[700, 620], 2020).is_empty());
+        assert_eq!(all_triples(&[700, 700, 620], 2020), [[620, 700, 700]]);
+        assert_eq!(all_triples(&[674, 673, 673], 2020), [[673, 673, 674]]);
+        assert!(all_triples(&[674, 673, 673], 2019).is_empty());
+        assert_eq!(all_triples(&[673, 673, 673], 2019), [[673, 673, 673]]);
+        assert!(all_triples(&[0, 0], 0).is_empty());
+        assert_eq!(all_triples(&[0, 0, 0], 0), [[0, 0, 0]]);
+    }
+
+    #[test]
+    fn every_match() {
+        let report = [1721, 979, 366, 299, 675, 1456, 1000, 1020, 1020, 5, 15, 985];
+        assert_eq!(all_pairs(&report, 2020), [[299, 1721], [1000, 1020]]);
+        assert_eq!(
+            all_triples(&report, 2020),
+            [[15, 985, 1020], [366, 675, 979]]
+        );
+    }
+
+    #[test]
+    fn no_overflow() {
+        const BIG: i64 = 9_000_000_000_000_000_000;
+        let report = [BIG, 1, 2, -BIG, i64::MAX, i64::MIN];
+        assert!(all_pairs(&report, -BIG).is_empty());
+        assert_eq!(all_pairs(&report, 0), [[-BIG, BIG]]);
+        assert_eq!(all_pairs(&report, i64::MIN + 1), [[i64::MIN, 1]]);
+        assert!(all_triples(&report, -BIG).is_empty());
+        assert_eq!(all_triples(&report, i64::MAX), [[-BIG, BIG, i64::MAX]]);
+        assert_eq!(all_triples(&report, i64::MIN + 3), [[i64::MIN, 1, 2]]);
+    }
+
+    /// Checks against every combination of indices.
+    #[test]
+    fn matches_brute_force() {
+        let report = (0..60).map(|i: i64| (i * 37) % 25 * 10).collect::<Vec<_>>();
+
+        for target in (0..800).step_by(10) {
+            let mut pairs = Vec::new();
+            let mut triples = Vec::new();
+            for i in 0..report.len() {
+                for j in i + 1..report.len() {
+                    let mut pair = [report[i], report[j]];
+                    pair.sort_unstable();
+                    if pair.iter().sum::<i64>() == target {
+                        pairs.push(pair);
+                    }
+                    for k in j + 1..report.len() {
+                        let mut triple = [report[i], report[j], report[k]];
+                        triple.sort_unstable();
+                        if triple.iter().sum::<i64>() == target {
+                            triples.push(triple);
+                        }
+                    }
+                }
+            }
+            pairs.sort_unstable();
+            pairs.dedup();
+            triples.sort_unstable();
+            triples.dedup();
+
+            assert_eq!(all_pairs(&report, target), pairs);
+            assert_eq!(all_triples(&report, target), triples);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! The command line both parts share, which differ only in their password policy.
+
+use crate::report::{audit, write_json_lines, write_table};
+use crate::PasswordPolicy;
+use std::io::{self, Read, Write};
+
+/// Writes how many passwords in `input` are valid under `rules` to `out`, and the lines which
+/// aren't to `err`. Lines that don't parse are always worth knowing about, rejected passwords
+/// only if `rejected` is set.
+pub fn write_count<W: Write, E: Write>(
+    mut out: W,
+    mut err: E,
+    rules: &impl PasswordPolicy,
+    input: &str,
+    rejected: bool,
+) -> io::Result<()> {
+    let outcome = crate::run(rules, input);
+    if rejected {
+        for invalid in &outcome.invalid {
+            writeln!(err, "{}", invalid)?;
+        }
+    } else {
+        for invalid in outcome.parse_errors() {
+            writeln!(err, "{}", invalid)?;
+        }
+    }
+    writeln!(out, "{}", outcome.valid)
+}
+
+/// Reads a password database from stdin. `--json` and `--table` show how every line fares
+/// under both policies. Otherwise, counts the passwords valid under `rules`, listing the rest
+/// with `--invalid`.
+pub fn run(rules: &impl PasswordPolicy) -> io::Result<()> {
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer)?;
+
+    let out = io::stdout().lock();
+    match std::env::args().nth(1).as_deref() {
+        Some("--json") => write_json_lines(out, audit(&buffer)),
+        Some("--table") => write_table(out, audit(&buffer)),
+        flag => write_count(out, io::stderr(), rules, &buffer, flag == Some("--invalid")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CountRange, PositionXor};
+
+    fn count(rules: &impl PasswordPolicy, rejected: bool) -> (String, String) {
+        let input = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c\n2-9 c: ccccccccc\n";
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        write_count(&mut out, &mut err, rules, input, rejected).unwrap();
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn output() {
+        let (out, err) = count(&CountRange, false);
+        assert_eq!(out, "2\n");
+        assert_eq!(err.lines().count(), 1);
+        assert!(err.starts_with("line 3: "));
+
+        let (out, err) = count(&PositionXor, true);
+        assert_eq!(out, "1\n");
+        let lines = err.lines().map(|line| &line[..7]).collect::<Vec<_>>();
+        assert_eq!(lines, ["line 2:", "line 3:", "line 4:"]);
+    }
+}
@@ -0,0 +1,322 @@
+//! Password policies for day 2 of Advent of Code 2020.
+//!
+//! Every line of the database is a [Policy] and a password, like `1-3 a: abcde`.
+//! What the policy means is up to a [PasswordPolicy]: part 1 reads it as [CountRange],
+//! part 2 as [PositionXor]. For how every line fares under both, see [report].
+
+use std::fmt;
+use std::str::FromStr;
+
+pub mod cli;
+pub mod report;
+
+/// Two numbers and a letter, like `1-3 a`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub lo: usize,
+    pub hi: usize,
+    pub letter: char,
+}
+
+impl FromStr for Policy {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (range, letter) = input
+            .split_once(' ')
+            .ok_or_else(|| ParseError::NoLetter(input.to_string()))?;
+        let (lo, hi) = range
+            .split_once('-')
+            .ok_or_else(|| ParseError::NoRange(range.to_string()))?;
+        let bound = |b: &str| b.parse().map_err(|_| ParseError::Bound(b.to_string()));
+
+        let mut chars = letter.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(ParseError::Letter(letter.to_string())),
+        };
+
+        Ok(Policy {
+            lo: bound(lo)?,
+            hi: bound(hi)?,
+            letter,
+        })
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}", self.lo, self.hi, self.letter)
+    }
+}
+
+/// A line of the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry<'a> {
+    pub policy: Policy,
+    pub password: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    pub fn parse(line: &'a str) -> Result<Self, ParseError> {
+        let (policy, password) = line
+            .split_once(": ")
+            .ok_or_else(|| ParseError::NoPassword(line.to_string()))?;
+        Ok(Entry {
+            policy: policy.parse()?,
+            password,
+        })
+    }
+}
+
+/// Why a line couldn't be parsed, along with the offending part of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The whole line, which has no `: ` before a password.
+    NoPassword(String),
+    /// The whole policy, which has no space before a letter.
+    NoLetter(String),
+    /// The range, which has no `-` between its numbers.
+    NoRange(String),
+    Bound(String),
+    /// Not exactly one character.
+    Letter(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NoPassword(line) => write!(f, "no password in {:?}", line),
+            ParseError::NoLetter(policy) => write!(f, "no letter in policy {:?}", policy),
+            ParseError::NoRange(range) => write!(f, "could not divide range {:?}", range),
+            ParseError::Bound(b) => write!(f, "could not parse number {:?}", b),
+            ParseError::Letter(l) => write!(f, "{:?} is not a single letter", l),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A way of reading a [Policy].
+pub trait PasswordPolicy {
+    /// Checks `password` against `policy`, saying why if it fails.
+    fn check(&self, policy: &Policy, password: &str) -> Result<(), Violation>;
+}
+
+/// Part 1: the letter must appear between `lo` and `hi` times, inclusive.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountRange;
+
+impl PasswordPolicy for CountRange {
+    fn check(&self, policy: &Policy, password: &str) -> Result<(), Violation> {
+        let count = password.matches(policy.letter).count();
+        if (policy.lo..=policy.hi).contains(&count) {
+            Ok(())
+        } else {
+            Err(Violation::Count(count))
+        }
+    }
+}
+
+/// Part 2: the letter must be at exactly one of the positions `lo` and `hi`, counting from 1.
+/// There's nothing at position 0 or past the end, so the letter isn't there either.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PositionXor;
+
+impl PasswordPolicy for PositionXor {
+    fn check(&self, policy: &Policy, password: &str) -> Result<(), Violation> {
+        let at = |position| letter_at(password, position) == Some(policy.letter);
+        match (at(policy.lo), at(policy.hi)) {
+            (true, true) => Err(Violation::Both),
+            (false, false) => Err(Violation::Neither),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The letter at `position` of `password`, counting from 1, if there is one.
+pub fn letter_at(password: &str, position: usize) -> Option<char> {
+    position
+        .checked_sub(1)
+        .and_then(|i| password.chars().nth(i))
+}
+
+/// Why a password failed its policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The letter appears this many times, out of range.
+    Count(usize),
+    /// The letter is at both positions.
+    Both,
+    /// The letter is at neither position.
+    Neither,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Count(count) => write!(f, "letter appears {} times", count),
+            Violation::Both => write!(f, "letter is at both positions"),
+            Violation::Neither => write!(f, "letter is at neither position"),
+        }
+    }
+}
+
+/// A line (counting from 1) which isn't a valid password, and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invalid {
+    pub line: usize,
+    pub reason: Reason,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    Parse(ParseError),
+    Rejected(Policy, Violation),
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Reason::Parse(err) => write!(f, "line {}: {}", self.line, err),
+            Reason::Rejected(policy, violation) => {
+                write!(f, "line {}: {}: {}", self.line, policy, violation)
+            }
+        }
+    }
+}
+
+/// How many passwords in a database are valid, and which lines aren't.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub valid: usize,
+    pub invalid: Vec<Invalid>,
+}
+
+impl Outcome {
+    /// The lines which couldn't be parsed, as opposed to those which were rejected.
+    pub fn parse_errors(&self) -> impl Iterator<Item = &Invalid> {
+        self.invalid
+            .iter()
+            .filter(|invalid| matches!(invalid.reason, Reason::Parse(_)))
+    }
+}
+
+/// Checks every line of `input` against `rules`.
+pub fn run(rules: &impl PasswordPolicy, input: &str) -> Outcome {
+    let mut outcome = Outcome::default();
+    for (line, text) in input.lines().enumerate() {
+        let reason = match Entry::parse(text) {
+            Ok(Entry { policy, password }) => match rules.check(&policy, password) {
+                Ok(()) => {
+                    outcome.valid += 1;
+                    continue;
+                }
+                Err(violation) => Reason::Rejected(policy, violation),
+            },
+            Err(err) => Reason::Parse(err),
+        };
+        outcome.invalid.push(Invalid {
+            line: line + 1,
+            reason,
+        });
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    #[test]
+    fn example() {
+        let outcome = run(&CountRange, EXAMPLE);
+        assert_eq!(outcome.valid, 2);
+        assert_eq!(
+            outcome.invalid,
+            [Invalid {
+                line: 2,
+                reason: Reason::Rejected(
+                    Policy {
+                        lo: 1,
+                        hi: 3,
+                        letter: 'b'
+                    },
+                    Violation::Count(0)
+                ),
+            }]
+        );
+
+        let outcome = run(&PositionXor, EXAMPLE);
+        assert_eq!(outcome.valid, 1);
+        let reasons = outcome
+            .invalid
+            .iter()
+            .map(|invalid| invalid.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            [
+                "line 2: 1-3 b: letter is at neither position",
+                "line 3: 2-9 c: letter is at both positions"
+            ]
+        );
+    }
+
+    #[test]
+    fn positions_out_of_range() {
+        let policy = |lo, hi| Policy {
+            lo,
+            hi,
+            letter: 'a',
+        };
+        assert_eq!(PositionXor.check(&policy(0, 1), "ab"), Ok(()));
+        assert_eq!(
+            PositionXor.check(&policy(0, 2), "ab"),
+            Err(Violation::Neither)
+        );
+        assert_eq!(PositionXor.check(&policy(1, 9), "ab"), Ok(()));
+        assert_eq!(PositionXor.check(&policy(1, 1), "ab"), Err(Violation::Both));
+        assert_eq!(CountRange.check(&policy(0, 0), "b"), Ok(()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |line| Entry::parse(line).map(|entry| entry.policy);
+        assert_eq!(
+            parse("1-3 a: abcde"),
+            Ok(Policy {
+                lo: 1,
+                hi: 3,
+                letter: 'a'
+            })
+        );
+        assert_eq!(
+            parse("1-3 a abcde"),
+            Err(ParseError::NoPassword("1-3 a abcde".to_string()))
+        );
+        assert_eq!(
+            parse("1-3a: abcde"),
+            Err(ParseError::NoLetter("1-3a".to_string()))
+        );
+        assert_eq!(
+            parse("13 a: abcde"),
+            Err(ParseError::NoRange("13".to_string()))
+        );
+        assert_eq!(
+            parse("1-x a: abcde"),
+            Err(ParseError::Bound("x".to_string()))
+        );
+        assert_eq!(
+            parse("1-3 ab: abcde"),
+            Err(ParseError::Letter("ab".to_string()))
+        );
+
+        let outcome = run(&PositionXor, "1-3 a: abcde\n\n1-3 a: bcd\n");
+        assert_eq!(outcome.valid, 1);
+        let lines = outcome.parse_errors().map(|invalid| invalid.line);
+        assert_eq!(lines.collect::<Vec<_>>(), [2]);
+    }
+}
@@ -0,0 +1,243 @@
+//! How every line of a database fares under both policies, for auditing rather than counting.
+
+use crate::{letter_at, CountRange, Entry, ParseError, PasswordPolicy, PositionXor, Violation};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// A line of the database (counting from 1), and what was found in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row<'a> {
+    pub line: usize,
+    pub audit: Result<Audit<'a>, ParseError>,
+}
+
+/// An entry checked against both policies, along with what each looked at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Audit<'a> {
+    pub entry: Entry<'a>,
+    /// How many times the letter appears in the password.
+    pub count: usize,
+    /// The letters at positions `lo` and `hi` of the password, if there are any.
+    pub positions: [Option<char>; 2],
+    pub count_range: Result<(), Violation>,
+    pub position_xor: Result<(), Violation>,
+}
+
+impl<'a> Audit<'a> {
+    pub fn new(entry: Entry<'a>) -> Self {
+        let Entry { policy, password } = entry;
+        Audit {
+            entry,
+            count: password.matches(policy.letter).count(),
+            positions: [
+                letter_at(password, policy.lo),
+                letter_at(password, policy.hi),
+            ],
+            count_range: CountRange.check(&policy, password),
+            position_xor: PositionXor.check(&policy, password),
+        }
+    }
+}
+
+/// Every line of `input`, parsed the same way as [crate::run] does.
+pub fn audit(input: &str) -> impl Iterator<Item = Row<'_>> {
+    input.lines().enumerate().map(|(line, text)| Row {
+        line: line + 1,
+        audit: Entry::parse(text).map(Audit::new),
+    })
+}
+
+/// Writes each row as a JSON object on its own line. Lines which don't parse only have an
+/// `error`.
+pub fn write_json_lines<'a, W: Write>(
+    mut out: W,
+    rows: impl IntoIterator<Item = Row<'a>>,
+) -> io::Result<()> {
+    for Row { line, audit } in rows {
+        let audit = match audit {
+            Ok(audit) => audit,
+            Err(err) => {
+                writeln!(
+                    out,
+                    r#"{{"line":{},"error":{}}}"#,
+                    line,
+                    json_string(&err.to_string())
+                )?;
+                continue;
+            }
+        };
+        let Entry { policy, password } = audit.entry;
+        let position = |letter: Option<char>| {
+            letter.map_or("null".to_string(), |c| json_string(&c.to_string()))
+        };
+        writeln!(
+            out,
+            r#"{{"line":{},"lo":{},"hi":{},"letter":{},"password":{},"count":{},"positions":[{},{}],"count_range":"{}","position_xor":"{}"}}"#,
+            line,
+            policy.lo,
+            policy.hi,
+            json_string(&policy.letter.to_string()),
+            json_string(password),
+            audit.count,
+            position(audit.positions[0]),
+            position(audit.positions[1]),
+            verdict(audit.count_range),
+            verdict(audit.position_xor),
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the rows as a table with a header, each column as wide as its widest cell.
+/// Lines which don't parse have their error in place of everything after the line number.
+pub fn write_table<'a, W: Write>(
+    mut out: W,
+    rows: impl IntoIterator<Item = Row<'a>>,
+) -> io::Result<()> {
+    const HEADER: [&str; 7] = [
+        "line",
+        "policy",
+        "password",
+        "count",
+        "positions",
+        "count range",
+        "position xor",
+    ];
+
+    let cells = rows
+        .into_iter()
+        .map(|Row { line, audit }| {
+            let audit = audit.map(|audit| {
+                let position = |letter: Option<char>| letter.unwrap_or('-');
+                [
+                    audit.entry.policy.to_string(),
+                    audit.entry.password.to_string(),
+                    audit.count.to_string(),
+                    format!(
+                        "{} {}",
+                        position(audit.positions[0]),
+                        position(audit.positions[1])
+                    ),
+                    verdict(audit.count_range).to_string(),
+                    verdict(audit.position_xor).to_string(),
+                ]
+            });
+            (line.to_string(), audit)
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = HEADER.map(|header| header.chars().count());
+    for (line, audit) in &cells {
+        widths[0] = widths[0].max(line.len());
+        if let Ok(audit) = audit {
+            for (width, cell) in widths[1..].iter_mut().zip(audit) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    // numbers go on the right, like they would in a spreadsheet
+    let numeric = [true, false, false, true, false, false, false];
+    let mut row = |line: &str, rest: Result<&[String], &ParseError>| -> io::Result<()> {
+        let mut text = format!("{:>1$}", line, widths[0]);
+        match rest {
+            Ok(rest) => {
+                for (i, cell) in rest.iter().enumerate() {
+                    let width = widths[i + 1];
+                    if numeric[i + 1] {
+                        write!(text, "  {:>1$}", cell, width).unwrap();
+                    } else {
+                        write!(text, "  {:<1$}", cell, width).unwrap();
+                    }
+                }
+            }
+            Err(err) => write!(text, "  {}", err).unwrap(),
+        }
+        writeln!(out, "{}", text.trim_end())
+    };
+
+    let header = HEADER.map(|header| header.to_string());
+    row(HEADER[0], Ok(&header[1..]))?;
+    for (line, audit) in &cells {
+        row(line, audit.as_ref().map(|audit| &audit[..]))?;
+    }
+    Ok(())
+}
+
+fn verdict(check: Result<(), Violation>) -> &'static str {
+    match check {
+        Ok(()) => "pass",
+        Err(_) => "fail",
+    }
+}
+
+/// `s` as a quoted JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n1-3 \"x\n";
+
+    fn written(write: fn(&mut Vec<u8>, Vec<Row>) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        write(&mut out, audit(EXAMPLE).collect()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn json_lines() {
+        let json = written(|out, rows| write_json_lines(out, rows));
+        let lines = json.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                r#"{"line":1,"lo":1,"hi":3,"letter":"a","password":"abcde","count":1,"positions":["a","c"],"count_range":"pass","position_xor":"pass"}"#,
+                r#"{"line":2,"lo":1,"hi":3,"letter":"b","password":"cdefg","count":0,"positions":["c","e"],"count_range":"fail","position_xor":"fail"}"#,
+                r#"{"line":3,"lo":2,"hi":9,"letter":"c","password":"ccccccccc","count":9,"positions":["c","c"],"count_range":"pass","position_xor":"fail"}"#,
+                r#"{"line":4,"error":"no password in \"1-3 \\\"x\""}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn table() {
+        let table = written(|out, rows| write_table(out, rows));
+        assert_eq!(
+            table,
+            "\
+line  policy  password   count  positions  count range  position xor
+   1  1-3 a   abcde          1  a c        pass         pass
+   2  1-3 b   cdefg          0  c e        fail         fail
+   3  2-9 c   ccccccccc      9  c c        pass         fail
+   4  no password in \"1-3 \\\"x\"
+"
+        );
+    }
+
+    #[test]
+    fn positions_past_the_end() {
+        let row = audit("0-4 a: ab").next().unwrap();
+        let audit = row.audit.unwrap();
+        assert_eq!(audit.positions, [None, None]);
+        assert_eq!(audit.position_xor, Err(Violation::Neither));
+        assert_eq!(audit.count_range, Ok(()));
+    }
+}
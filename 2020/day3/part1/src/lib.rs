@@ -0,0 +1,434 @@
+//! The map of trees for day 3 of Advent of Code 2020, and the toboggan rides down it.
+//!
+//! The map repeats forever to the right (and left), so every lookup wraps the column.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+pub mod render;
+
+const WORD: usize = u64::BITS as usize;
+
+/// A grid of trees and open squares, packed one bit per square, row after row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Forest {
+    width: usize,
+    height: usize,
+    trees: Vec<u64>,
+}
+
+impl Forest {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether there's a tree at `column` of `row`, counting from 0 at the top left, where the
+    /// column wraps around in either direction. There are no trees below the bottom.
+    #[inline]
+    pub fn is_tree(&self, column: isize, row: usize) -> bool {
+        if row >= self.height {
+            return false;
+        }
+        let column = column.rem_euclid(self.width as isize) as usize;
+        self.bit(row * self.width + column)
+    }
+
+    #[inline]
+    fn bit(&self, i: usize) -> bool {
+        self.trees[i / WORD] & (1 << (i % WORD)) != 0
+    }
+
+    /// How many trees a ride down `slope` hits, starting from the top left and stopping once
+    /// past the bottom.
+    pub fn trees_hit(&self, slope: Slope) -> usize {
+        self.path(slope)
+            .filter(|&(row, column)| self.bit(row * self.width + column))
+            .count()
+    }
+
+    /// Every square a ride down `slope` passes through, as `(row, column)` with the column
+    /// wrapped into the map.
+    pub fn path(&self, slope: Slope) -> impl Iterator<Item = (usize, usize)> {
+        // wrapping the step once up front keeps the column from ever overflowing
+        let (width, step) = (self.width, self.wrapped(slope.right));
+        (0..self.height)
+            .step_by(slope.down)
+            .scan(0, move |column, row| {
+                let at = *column;
+                *column = (at + step) % width;
+                Some((row, at))
+            })
+    }
+
+    /// `right` as a step between 0 and the width. An empty forest has nowhere to step.
+    fn wrapped(&self, right: isize) -> usize {
+        if self.width == 0 {
+            0
+        } else {
+            right.rem_euclid(self.width as isize) as usize
+        }
+    }
+
+    /// The product of the trees hit down every slope, unless it overflows.
+    pub fn trees_product(&self, slopes: &[Slope]) -> Option<u128> {
+        slopes.iter().try_fold(1u128, |product, &slope| {
+            product.checked_mul(self.trees_hit(slope) as u128)
+        })
+    }
+
+    /// The slope hitting the fewest trees, and how many it hits, out of every slope going
+    /// `rights` right and `downs` down. Going 0 down is skipped, as it never gets anywhere.
+    /// Ties go to the slope going least far down, then least far right.
+    pub fn best_slope(
+        &self,
+        rights: RangeInclusive<isize>,
+        downs: RangeInclusive<usize>,
+    ) -> Option<(Slope, usize)> {
+        downs
+            .flat_map(|down| rights.clone().map(move |right| Slope::new(right, down)))
+            .filter_map(Result::ok)
+            .map(|slope| (slope, self.trees_hit(slope)))
+            .min_by_key(|&(_, hit)| hit)
+    }
+}
+
+impl FromStr for Forest {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut forest = Forest {
+            width: 0,
+            height: 0,
+            trees: Vec::new(),
+        };
+
+        for (row, text) in input.lines().enumerate() {
+            let width = text.chars().count();
+            if row == 0 {
+                if width == 0 {
+                    return Err(ParseError::Empty { line: 1 });
+                }
+                forest.width = width;
+            } else if width != forest.width {
+                return Err(ParseError::Ragged {
+                    line: row + 1,
+                    width,
+                    expected: forest.width,
+                });
+            }
+
+            let start = row * forest.width;
+            forest.trees.resize((start + width).div_ceil(WORD), 0);
+            for (column, square) in text.chars().enumerate() {
+                match square {
+                    '#' => {
+                        let i = start + column;
+                        forest.trees[i / WORD] |= 1 << (i % WORD);
+                    }
+                    '.' => {}
+                    found => {
+                        return Err(ParseError::Square {
+                            line: row + 1,
+                            column: column + 1,
+                            found,
+                        })
+                    }
+                }
+            }
+            forest.height += 1;
+        }
+        Ok(forest)
+    }
+}
+
+/// Why a map couldn't be parsed, with lines and columns counting from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The first line is blank, so the map has no width to repeat.
+    Empty { line: usize },
+    /// A line that isn't as wide as the first.
+    Ragged {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    /// Neither a tree (`#`) nor open (`.`).
+    Square {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty { line } => write!(f, "line {}: map has no width", line),
+            ParseError::Ragged {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {}: {} squares wide, but the map is {}",
+                line, width, expected
+            ),
+            ParseError::Square {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: {:?} is neither '#' nor '.'",
+                line, column, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How far a toboggan moves right (or left, if negative) for every `down` rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slope {
+    right: isize,
+    down: usize,
+}
+
+impl Slope {
+    /// Going nowhere down never reaches the bottom, so `down` must be at least 1.
+    pub fn new(right: isize, down: usize) -> Result<Self, SlopeError> {
+        if down == 0 {
+            return Err(SlopeError::Flat);
+        }
+        Ok(Slope { right, down })
+    }
+
+    pub fn right(&self) -> isize {
+        self.right
+    }
+
+    pub fn down(&self) -> usize {
+        self.down
+    }
+}
+
+/// Parses `right,down`, like `3,1` or `-1,2`.
+impl FromStr for Slope {
+    type Err = SlopeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (right, down) = input
+            .split_once(',')
+            .ok_or_else(|| SlopeError::NoComma(input.to_string()))?;
+        let right = right
+            .trim()
+            .parse()
+            .map_err(|_| SlopeError::Right(right.to_string()))?;
+        let down = down
+            .trim()
+            .parse()
+            .map_err(|_| SlopeError::Down(down.to_string()))?;
+        Slope::new(right, down)
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// Why a slope couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlopeError {
+    NoComma(String),
+    Right(String),
+    Down(String),
+    /// `down` is 0.
+    Flat,
+}
+
+impl fmt::Display for SlopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlopeError::NoComma(s) => write!(f, "slope {:?} is not `right,down`", s),
+            SlopeError::Right(r) => write!(f, "could not parse distance right {:?}", r),
+            SlopeError::Down(d) => write!(f, "could not parse distance down {:?}", d),
+            SlopeError::Flat => write!(f, "slope must go down at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for SlopeError {}
+
+/// Parses an inclusive range like `-3..=5`, or a single number like `5` as a range of one.
+pub fn parse_range<T: FromStr + Copy>(input: &str) -> Result<RangeInclusive<T>, RangeError> {
+    let bound = |b: &str| b.trim().parse().map_err(|_| RangeError(input.to_string()));
+    match input.split_once("..=") {
+        Some((lo, hi)) => Ok(bound(lo)?..=bound(hi)?),
+        None => bound(input).map(|n| n..=n),
+    }
+}
+
+/// A range which isn't `lo..=hi` or a single number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeError(pub String);
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "range {:?} is not `lo..=hi` or a number", self.0)
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    /// Walks the text itself, the way the old binaries did.
+    fn walk(input: &str, slope: Slope) -> usize {
+        let lines = input.lines().collect::<Vec<_>>();
+        let mut column = 0isize;
+        let mut trees = 0;
+        for line in lines.iter().step_by(slope.down()) {
+            let chars = line.chars().collect::<Vec<_>>();
+            if chars[column as usize] == '#' {
+                trees += 1;
+            }
+            column = (column + slope.right()).rem_euclid(chars.len() as isize);
+        }
+        trees
+    }
+
+    #[test]
+    fn example() {
+        let forest = EXAMPLE.parse::<Forest>().unwrap();
+        assert_eq!((forest.width(), forest.height()), (11, 11));
+
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .iter()
+            .map(|&(right, down)| Slope::new(right, down).unwrap())
+            .collect::<Vec<_>>();
+        let hit = slopes
+            .iter()
+            .map(|&slope| forest.trees_hit(slope))
+            .collect::<Vec<_>>();
+        assert_eq!(hit, [2, 7, 3, 4, 2]);
+        assert_eq!(forest.trees_product(&slopes), Some(336));
+    }
+
+    #[test]
+    fn any_slope() {
+        let forest = EXAMPLE.parse::<Forest>().unwrap();
+        for right in -25..25 {
+            for down in 1..13 {
+                let slope = Slope::new(right, down).unwrap();
+                assert_eq!(forest.trees_hit(slope), walk(EXAMPLE, slope), "{}", slope);
+            }
+        }
+        let far = Slope::new(isize::MIN, 1).unwrap();
+        assert_eq!(forest.trees_hit(far), walk(EXAMPLE, far));
+    }
+
+    #[test]
+    fn best_slope() {
+        let forest = EXAMPLE.parse::<Forest>().unwrap();
+        assert_eq!(
+            forest.best_slope(1..=7, 1..=1),
+            Some((Slope::new(2, 1).unwrap(), 1))
+        );
+        assert_eq!(
+            forest.best_slope(-2..=0, 0..=2),
+            Some((Slope::new(0, 2).unwrap(), 1))
+        );
+        // 2,2 hits as few, but goes further down
+        assert_eq!(
+            forest.best_slope(0..=2, 1..=2),
+            Some((Slope::new(2, 1).unwrap(), 1))
+        );
+        let (best, hit) = forest.best_slope(-12..=12, 1..=12).unwrap();
+        assert_eq!(hit, walk(EXAMPLE, best));
+        for right in -12..=12 {
+            for down in 1..=12 {
+                assert!(walk(EXAMPLE, Slope::new(right, down).unwrap()) >= hit);
+            }
+        }
+        assert_eq!(
+            forest.best_slope(parse_range("1..=0").unwrap(), 1..=2),
+            None
+        );
+        assert_eq!(forest.best_slope(1..=3, 0..=0), None);
+
+        assert_eq!(parse_range::<isize>("-3..=5"), Ok(-3..=5));
+        assert_eq!(parse_range::<usize>("2"), Ok(2..=2));
+        assert_eq!(
+            parse_range::<usize>("1..5"),
+            Err(RangeError("1..5".to_string()))
+        );
+    }
+
+    #[test]
+    fn wrapped_lookups() {
+        let forest = EXAMPLE.parse::<Forest>().unwrap();
+        assert!(forest.is_tree(2, 0));
+        assert!(forest.is_tree(13, 0));
+        assert!(forest.is_tree(-9, 0));
+        assert!(!forest.is_tree(-1, 0));
+        assert!(!forest.is_tree(2, 11));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "..#\n.#\n".parse::<Forest>(),
+            Err(ParseError::Ragged {
+                line: 2,
+                width: 2,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            "..#\n.x.\n".parse::<Forest>(),
+            Err(ParseError::Square {
+                line: 2,
+                column: 2,
+                found: 'x'
+            })
+        );
+        assert_eq!("\n".parse::<Forest>(), Err(ParseError::Empty { line: 1 }));
+        assert_eq!(
+            "".parse::<Forest>()
+                .unwrap()
+                .trees_hit(Slope::new(3, 1).unwrap()),
+            0
+        );
+
+        assert_eq!("-1, 2".parse(), Ok(Slope::new(-1, 2).unwrap()));
+        assert_eq!("3".parse::<Slope>(), Err(SlopeError::NoComma("3".into())));
+        assert_eq!("x,1".parse::<Slope>(), Err(SlopeError::Right("x".into())));
+        assert_eq!("1,-1".parse::<Slope>(), Err(SlopeError::Down("-1".into())));
+        assert_eq!("1,0".parse::<Slope>(), Err(SlopeError::Flat));
+        assert_eq!(Slope::new(3, 0), Err(SlopeError::Flat));
+    }
+}
@@ -1,18 +1,43 @@
+use day3_part1::render::render;
+use day3_part1::{Forest, Slope};
+use std::fmt;
 use std::io::{self, Read};
 
 fn main() -> io::Result<()> {
     let mut buffer = String::new();
     io::stdin().read_to_string(&mut buffer)?;
 
-    const SLOPE: usize = 3;
+    let forest: Forest = exit_on_error(buffer.parse());
 
-    let num_trees = buffer.lines().enumerate()
-        .fold(0, |count, (line_num, tree_row)| {
-            let check_idx = (line_num * SLOPE) % tree_row.len();
-            if tree_row.chars().nth(check_idx) == Some('#') {count + 1} else {count}
-        });
+    // `--render 3,1` draws that ride over the map
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let [flag, slope] = &args[..] {
+        if flag == "--render" {
+            print!("{}", render(&forest, exit_on_error(slope.parse())));
+            return Ok(());
+        }
+    }
 
-    println!("{}", num_trees);
+    // slopes can be given as arguments, like `3,1 -1,2`
+    let mut slopes: Vec<Slope> = exit_on_error(args.iter().map(|arg| arg.parse()).collect());
+    if slopes.is_empty() {
+        slopes = [(3, 1)]
+            .iter()
+            .map(|&(right, down)| Slope::new(right, down))
+            .collect::<Result<_, _>>()
+            .expect("default slopes all go down");
+    }
+
+    for slope in slopes {
+        println!("{}", forest.trees_hit(slope));
+    }
 
     Ok(())
 }
+
+fn exit_on_error<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    })
+}
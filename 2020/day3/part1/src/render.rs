@@ -0,0 +1,45 @@
+//! Drawing a ride over the map.
+
+use crate::{Forest, Slope};
+
+/// Draws `forest` as it was parsed, with every square a ride down `slope` passes through
+/// marked `O` if it's open or `X` if it's a tree.
+pub fn render(forest: &Forest, slope: Slope) -> String {
+    let mut path = forest.path(slope).peekable();
+    let mut drawing = String::with_capacity((forest.width() + 1) * forest.height());
+
+    for row in 0..forest.height() {
+        let on_path = path.next_if(|&(r, _)| r == row).map(|(_, column)| column);
+        for column in 0..forest.width() {
+            let tree = forest.is_tree(column as isize, row);
+            drawing.push(match (on_path == Some(column), tree) {
+                (true, true) => 'X',
+                (true, false) => 'O',
+                (false, true) => '#',
+                (false, false) => '.',
+            });
+        }
+        drawing.push('\n');
+    }
+    drawing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let forest = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n"
+            .parse::<Forest>()
+            .unwrap();
+        assert_eq!(
+            render(&forest, Slope::new(3, 1).unwrap()),
+            "O.##.......\n#..O#...#..\n.#....X..#.\n..#.#...#O#\n"
+        );
+        assert_eq!(
+            render(&forest, Slope::new(-1, 2).unwrap()),
+            "O.##.......\n#...#...#..\n.#....#..#O\n..#.#...#.#\n"
+        );
+    }
+}
@@ -1,24 +1,49 @@
+use day3_part1::{parse_range, Forest, Slope};
+use std::fmt;
 use std::io::{self, Read};
 
 fn main() -> io::Result<()> {
     let mut buffer = String::new();
     io::stdin().read_to_string(&mut buffer)?;
 
-    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+    let forest: Forest = exit_on_error(buffer.parse());
 
-    // TODO: (not that I ever will) 
-    // could maybe speed up for large cases by mapping string into vec of bools first 
-    // (then iterating over chunks instead of lines)
+    // `--best -10..=10 1..=5` finds the slope in those ranges of right and down hitting the
+    // fewest trees
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let [flag, rights, downs] = &args[..] {
+        if flag == "--best" {
+            let rights = exit_on_error(parse_range(rights));
+            let downs = exit_on_error(parse_range(downs));
+            match forest.best_slope(rights, downs) {
+                Some((slope, hit)) => println!("{}: {}", slope, hit),
+                None => exit_on_error(Err("no slopes in range")),
+            }
+            return Ok(());
+        }
+    }
 
-    let trees_product = slopes.iter().fold(1, |product, (right, down)| {
-        product * buffer.lines().step_by(*down).enumerate()
-            .fold(0, |count, (line_num, tree_row)| {
-                let check_idx = (line_num * right) % tree_row.len();
-                if tree_row.chars().nth(check_idx) == Some('#') {count + 1} else {count}
-            })
-    });
+    // slopes can be given as arguments, like `3,1 -1,2`
+    let mut slopes: Vec<Slope> = exit_on_error(args.iter().map(|arg| arg.parse()).collect());
+    if slopes.is_empty() {
+        slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .iter()
+            .map(|&(right, down)| Slope::new(right, down))
+            .collect::<Result<_, _>>()
+            .expect("default slopes all go down");
+    }
 
-    println!("{}", trees_product);
+    match forest.trees_product(&slopes) {
+        Some(product) => println!("{}", product),
+        None => exit_on_error(Err("product of trees hit overflows")),
+    }
 
     Ok(())
 }
+
+fn exit_on_error<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    })
+}
@@ -0,0 +1,372 @@
+//! Passports for day 4 of Advent of Code 2020, and the rules for which ones are valid.
+//!
+//! A batch file is passports separated by blank lines, each a list of `name:value` fields
+//! separated by spaces or newlines. What makes a passport valid is a [Schema]: part 1 only
+//! asks that the required fields are there, part 2 that they follow their rules too.
+//! To see why a passport was rejected, see [report].
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+pub mod report;
+
+/// A passport as it appears in the batch file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Passport<'a> {
+    /// The lines it's on, counting from 1.
+    pub lines: RangeInclusive<usize>,
+    /// Every field, in order, including any repeats. A field without a `:` has an empty value.
+    pub fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Passport<'a> {
+    /// The values of every field called `name`.
+    pub fn values<'p>(&'p self, name: &'p str) -> impl Iterator<Item = &'a str> + 'p {
+        self.fields
+            .iter()
+            .filter(move |&&(n, _)| n == name)
+            .map(|&(_, value)| value)
+    }
+}
+
+/// Every passport in a batch file.
+pub fn passports(input: &str) -> Vec<Passport<'_>> {
+    let mut passports = Vec::new();
+    let mut current: Option<Passport> = None;
+
+    for (line, text) in input.lines().enumerate() {
+        let line = line + 1;
+        if text.trim().is_empty() {
+            passports.extend(current.take());
+            continue;
+        }
+        let passport = current.get_or_insert_with(|| Passport {
+            lines: line..=line,
+            fields: Vec::new(),
+        });
+        passport.lines = *passport.lines.start()..=line;
+        passport.fields.extend(
+            text.split_ascii_whitespace()
+                .map(|field| field.split_once(':').unwrap_or((field, ""))),
+        );
+    }
+    passports.extend(current);
+    passports
+}
+
+/// What a field's value has to look like.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// Anything at all.
+    Any,
+    /// A number in the range, written in plain decimal digits.
+    Range(RangeInclusive<u32>),
+    /// A number followed by a unit, in the range for that unit, like `183cm`.
+    Units(Vec<(String, RangeInclusive<u32>)>),
+    /// `#` followed by six hex digits, like `#a97842`.
+    HexColour,
+    /// Exactly one of the listed values.
+    OneOf(Vec<String>),
+    /// Exactly this many decimal digits, leading zeroes and all.
+    Digits(usize),
+}
+
+impl Rule {
+    pub fn check(&self, value: &str) -> bool {
+        self.validate(value).is_ok()
+    }
+
+    /// Checks `value`, saying how it falls short if it does.
+    pub fn validate(&self, value: &str) -> Result<(), Failure> {
+        match self {
+            Rule::Any => Ok(()),
+            Rule::Range(range) => in_range(value, range),
+            Rule::Units(units) => {
+                let (number, range) = units
+                    .iter()
+                    .find_map(|(unit, range)| Some((value.strip_suffix(unit.as_str())?, range)))
+                    .ok_or_else(|| {
+                        Failure::NoUnit(units.iter().map(|(unit, _)| unit.clone()).collect())
+                    })?;
+                in_range(number, range)
+            }
+            Rule::HexColour => value
+                .strip_prefix('#')
+                .filter(|hex| hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .map(|_| ())
+                .ok_or(Failure::NotHexColour),
+            Rule::OneOf(values) if values.iter().any(|v| v == value) => Ok(()),
+            Rule::OneOf(values) => Err(Failure::NotOneOf(values.clone())),
+            Rule::Digits(len) if value.len() == *len && is_digits(value) => Ok(()),
+            Rule::Digits(len) => Err(Failure::NotDigits(*len)),
+        }
+    }
+}
+
+/// How a value fell short of its [Rule].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    NotANumber,
+    OutOfRange(RangeInclusive<u32>),
+    /// Doesn't end in any of these units.
+    NoUnit(Vec<String>),
+    NotHexColour,
+    NotOneOf(Vec<String>),
+    /// Isn't exactly this many digits.
+    NotDigits(usize),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::NotANumber => write!(f, "not a number"),
+            Failure::OutOfRange(range) => write!(f, "out of {}..={}", range.start(), range.end()),
+            Failure::NoUnit(units) => write!(f, "not in {}", units.join(" or ")),
+            Failure::NotHexColour => write!(f, "not a hex colour"),
+            Failure::NotOneOf(values) => write!(f, "not one of {}", values.join(", ")),
+            Failure::NotDigits(len) => write!(f, "not {} digits", len),
+        }
+    }
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn in_range(number: &str, range: &RangeInclusive<u32>) -> Result<(), Failure> {
+    // anything with only digits that doesn't fit is too big for the range anyway
+    let n = match number.parse::<u32>() {
+        _ if !is_digits(number) => return Err(Failure::NotANumber),
+        Ok(n) => n,
+        Err(_) => u32::MAX,
+    };
+    if range.contains(&n) {
+        Ok(())
+    } else {
+        Err(Failure::OutOfRange(range.clone()))
+    }
+}
+
+/// A field a [Schema] knows about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldRule {
+    pub name: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// Which fields a passport has to have, and what their values have to look like.
+/// Fields the schema doesn't know about are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub fields: Vec<FieldRule>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    pub fn required(mut self, name: &str, rule: Rule) -> Self {
+        self.fields.push(FieldRule {
+            name: name.to_string(),
+            required: true,
+            rule,
+        });
+        self
+    }
+
+    pub fn optional(mut self, name: &str, rule: Rule) -> Self {
+        self.fields.push(FieldRule {
+            name: name.to_string(),
+            required: false,
+            rule,
+        });
+        self
+    }
+
+    /// The rules from the puzzle, with the country ID optional.
+    pub fn north_pole() -> Self {
+        let colours = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+        Schema::new()
+            .required("byr", Rule::Range(1920..=2002))
+            .required("iyr", Rule::Range(2010..=2020))
+            .required("eyr", Rule::Range(2020..=2030))
+            .required(
+                "hgt",
+                Rule::Units(vec![
+                    ("cm".to_string(), 150..=193),
+                    ("in".to_string(), 59..=76),
+                ]),
+            )
+            .required("hcl", Rule::HexColour)
+            .required(
+                "ecl",
+                Rule::OneOf(colours.iter().map(|c| c.to_string()).collect()),
+            )
+            .required("pid", Rule::Digits(9))
+            .optional("cid", Rule::Any)
+    }
+
+    /// The same fields, but allowing any value, which is all part 1 asks for.
+    pub fn presence_only(mut self) -> Self {
+        for field in &mut self.fields {
+            field.rule = Rule::Any;
+        }
+        self
+    }
+
+    /// The rule for the field called `name`, if the schema knows about it.
+    pub fn rule(&self, name: &str) -> Option<&Rule> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.rule)
+    }
+
+    /// Whether `passport` has every required field, whatever their values.
+    pub fn has_required(&self, passport: &Passport) -> bool {
+        self.fields
+            .iter()
+            .filter(|field| field.required)
+            .all(|field| passport.values(&field.name).next().is_some())
+    }
+
+    /// Whether `passport` has every required field, and every field the schema knows about
+    /// follows its rule, however many times it appears.
+    pub fn is_valid(&self, passport: &Passport) -> bool {
+        self.has_required(passport)
+            && self.fields.iter().all(|field| {
+                passport
+                    .values(&field.name)
+                    .all(|value| field.rule.check(value))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in
+";
+
+    #[test]
+    fn example() {
+        let schema = Schema::north_pole();
+        let passports = passports(EXAMPLE);
+        let lines = passports
+            .iter()
+            .map(|p| p.lines.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [1..=2, 4..=5, 7..=10, 12..=13]);
+
+        let present = passports.iter().map(|p| schema.has_required(p));
+        assert_eq!(present.collect::<Vec<_>>(), [true, false, true, false]);
+    }
+
+    #[test]
+    fn rules() {
+        let schema = Schema::north_pole();
+        let rule = |name| schema.rule(name).unwrap();
+        assert!(rule("byr").check("2002"));
+        assert!(!rule("byr").check("2003"));
+        assert!(!rule("byr").check("+2000"));
+        assert!(rule("hgt").check("60in"));
+        assert!(rule("hgt").check("190cm"));
+        assert!(!rule("hgt").check("190in"));
+        assert!(!rule("hgt").check("190"));
+        assert!(!rule("hgt").check("cm"));
+        assert!(rule("hcl").check("#123abc"));
+        assert!(!rule("hcl").check("#123abz"));
+        assert!(!rule("hcl").check("123abc"));
+        assert!(!rule("hcl").check("#"));
+        assert!(rule("ecl").check("brn"));
+        assert!(!rule("ecl").check("wat"));
+        assert!(rule("pid").check("000000001"));
+        assert!(!rule("pid").check("0123456789"));
+        assert!(!rule("pid").check("-12345678"));
+        assert!(!rule("byr").check("99999999999"));
+
+        let failure = |name, value| rule(name).validate(value).unwrap_err().to_string();
+        assert_eq!(failure("hgt", "190in"), "out of 59..=76");
+        assert_eq!(failure("hgt", "190"), "not in cm or in");
+        assert_eq!(failure("hgt", "xcm"), "not a number");
+        assert_eq!(failure("byr", "99999999999"), "out of 1920..=2002");
+        assert_eq!(failure("hcl", "#12345"), "not a hex colour");
+        assert_eq!(
+            failure("ecl", "wat"),
+            "not one of amb, blu, brn, gry, grn, hzl, oth"
+        );
+        assert_eq!(failure("pid", "1234"), "not 9 digits");
+    }
+
+    #[test]
+    fn valid_passports() {
+        let invalid = "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007
+";
+        let valid = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+";
+        let schema = Schema::north_pole();
+        assert!(passports(invalid).iter().all(|p| !schema.is_valid(p)));
+        assert!(passports(valid).iter().all(|p| schema.is_valid(p)));
+    }
+
+    #[test]
+    fn odd_fields() {
+        let schema = Schema::new().required("ab", Rule::Any);
+        // too short to slice, no colon, or repeated: none of them panic
+        let passports = passports("a ab\n\n\n\nab:1 ab:2 x:\n");
+        assert_eq!(passports.len(), 2);
+        assert_eq!(passports[0].fields, [("a", ""), ("ab", "")]);
+        assert_eq!(passports[1].lines, 5..=5);
+        assert_eq!(passports[1].values("ab").collect::<Vec<_>>(), ["1", "2"]);
+        assert!(schema.has_required(&passports[0]));
+
+        let schema = Schema::new().required("ab", Rule::Digits(1));
+        assert!(!schema.is_valid(&passports[0]));
+        assert!(schema.is_valid(&passports[1]));
+        assert!(!schema.is_valid(&Passport {
+            lines: 1..=1,
+            fields: vec![("ab", "1"), ("ab", "x")],
+        }));
+    }
+}
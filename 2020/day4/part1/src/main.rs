@@ -1,25 +1,26 @@
+use day4_part1::report::reports;
+use day4_part1::{passports, Schema};
 use std::io::{self, Read};
 
 fn main() -> io::Result<()> {
     let mut buffer = String::new();
     io::stdin().read_to_string(&mut buffer)?;
 
-    let num_valid = buffer.split("\n\n").fold(0, |count, passport| {
-        let mut has_cred: [bool; 7] = [false; 7];
-        for cred in passport.split_ascii_whitespace() {
-            match &cred[0..3] {
-                "byr" => has_cred[0] = true,
-                "iyr" => has_cred[1] = true,
-                "eyr" => has_cred[2] = true,
-                "hgt" => has_cred[3] = true,
-                "hcl" => has_cred[4] = true,
-                "ecl" => has_cred[5] = true,
-                "pid" => has_cred[6] = true,
-                _ => (),
-            }
+    // part 1 only looks at which fields are there
+    let schema = Schema::north_pole().presence_only();
+
+    // `--report` says why each passport is valid or not, rather than counting them
+    if std::env::args().nth(1).as_deref() == Some("--report") {
+        for report in reports(&schema, &buffer) {
+            println!("{}", report);
         }
-        if has_cred.contains(&false) {count} else {count + 1}
-    });
+        return Ok(());
+    }
+
+    let num_valid = passports(&buffer)
+        .iter()
+        .filter(|passport| schema.is_valid(passport))
+        .count();
 
     println!("{}", num_valid);
 
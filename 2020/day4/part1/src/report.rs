@@ -0,0 +1,207 @@
+//! Why each passport was accepted or rejected, for reviewing a batch rather than counting it.
+
+use crate::{passports, Failure, Passport, Schema};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Everything a [Schema] has to say about one passport.
+///
+/// Only missing and failed fields make a passport invalid. Unknown and duplicate fields are
+/// reported so they can be looked at, but are otherwise treated as [Schema::is_valid] does:
+/// unknown fields are ignored, and every copy of a duplicate has to pass its rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report<'a> {
+    /// Where the passport is in the batch, counting from 1.
+    pub index: usize,
+    /// The lines it's on, counting from 1.
+    pub lines: RangeInclusive<usize>,
+    /// Required fields it doesn't have, in the order the schema lists them.
+    pub missing: Vec<String>,
+    /// Fields whose values broke their rule, in the order they appear.
+    pub failed: Vec<Failed<'a>>,
+    /// Fields the schema doesn't know about, each once, in the order they first appear.
+    pub unknown: Vec<&'a str>,
+    /// Fields which appear more than once, each once, in the order they first appear.
+    pub duplicates: Vec<&'a str>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(schema: &Schema, index: usize, passport: &Passport<'a>) -> Self {
+        let mut report = Report {
+            index,
+            lines: passport.lines.clone(),
+            missing: Vec::new(),
+            failed: Vec::new(),
+            unknown: Vec::new(),
+            duplicates: Vec::new(),
+        };
+
+        for field in schema.fields.iter().filter(|field| field.required) {
+            if passport.values(&field.name).next().is_none() {
+                report.missing.push(field.name.clone());
+            }
+        }
+
+        for (i, &(name, value)) in passport.fields.iter().enumerate() {
+            match schema.rule(name) {
+                Some(rule) => {
+                    if let Err(failure) = rule.validate(value) {
+                        report.failed.push(Failed {
+                            name,
+                            value,
+                            failure,
+                        });
+                    }
+                }
+                None if !report.unknown.contains(&name) => report.unknown.push(name),
+                None => {}
+            }
+
+            let seen_before = passport.fields[..i].iter().any(|&(n, _)| n == name);
+            if seen_before && !report.duplicates.contains(&name) {
+                report.duplicates.push(name);
+            }
+        }
+        report
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// A field whose value broke its rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failed<'a> {
+    pub name: &'a str,
+    pub value: &'a str,
+    pub failure: Failure,
+}
+
+impl fmt::Display for Failed<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} {}", self.name, self.value, self.failure)
+    }
+}
+
+/// A heading line, then a line for each kind of problem the passport has.
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "passport {} (lines {}-{}): {}",
+            self.index,
+            self.lines.start(),
+            self.lines.end(),
+            if self.is_valid() { "valid" } else { "invalid" }
+        )?;
+
+        let failed = self.failed.iter().map(|failed| failed.to_string());
+        let sections = [
+            ("missing", self.missing.clone()),
+            ("failed", failed.collect()),
+            (
+                "unknown",
+                self.unknown.iter().map(|s| s.to_string()).collect(),
+            ),
+            (
+                "duplicate",
+                self.duplicates.iter().map(|s| s.to_string()).collect(),
+            ),
+        ];
+        for (heading, items) in sections.iter().filter(|(_, items)| !items.is_empty()) {
+            // a failure can list values itself, so keep the fields apart more clearly
+            let separator = if *heading == "failed" { "; " } else { ", " };
+            write!(f, "\n  {}: {}", heading, items.join(separator))?;
+        }
+        Ok(())
+    }
+}
+
+/// A report on every passport in a batch file.
+pub fn reports<'a>(schema: &Schema, input: &'a str) -> Vec<Report<'a>> {
+    passports(input)
+        .iter()
+        .enumerate()
+        .map(|(i, passport)| Report::new(schema, i + 1, passport))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics() {
+        let input = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f cid:1
+
+hgt:190in ecl:grn iyr:2012 iyr:2013 eyr:2030 byr:1980
+hcl:#623a2f hair:long pid:12 hair:short
+";
+        let schema = Schema::north_pole();
+        let reports = reports(&schema, input);
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].is_valid());
+        assert_eq!(reports[0].to_string(), "passport 1 (lines 1-2): valid");
+
+        assert_eq!(
+            reports[1],
+            Report {
+                index: 2,
+                lines: 4..=5,
+                missing: Vec::new(),
+                failed: vec![
+                    Failed {
+                        name: "hgt",
+                        value: "190in",
+                        failure: Failure::OutOfRange(59..=76),
+                    },
+                    Failed {
+                        name: "pid",
+                        value: "12",
+                        failure: Failure::NotDigits(9),
+                    },
+                ],
+                unknown: vec!["hair"],
+                duplicates: vec!["iyr", "hair"],
+            }
+        );
+        assert_eq!(
+            reports[1].to_string(),
+            "\
+passport 2 (lines 4-5): invalid
+  failed: hgt:190in out of 59..=76; pid:12 not 9 digits
+  unknown: hair
+  duplicate: iyr, hair"
+        );
+
+        let report = Report::new(&schema, 3, &passports("ecl:grn\n")[0]);
+        assert_eq!(report.missing, ["byr", "iyr", "eyr", "hgt", "hcl", "pid"]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn agrees_with_schema() {
+        let input = "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+hcl:dab227 iyr:2012
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719 pid:1
+";
+        for schema in &[Schema::north_pole(), Schema::north_pole().presence_only()] {
+            let passports = passports(input);
+            for (report, passport) in reports(schema, input).iter().zip(&passports) {
+                assert_eq!(report.is_valid(), schema.is_valid(passport));
+            }
+        }
+    }
+}
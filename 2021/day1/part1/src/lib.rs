@@ -0,0 +1,74 @@
+//! Sonar sweeps for day 1 of Advent of Code 2021.
+
+pub mod stats;
+
+/// Counts how often the sum of a window of `k` depths is larger than the sum of the window
+/// just before it. `k = 1` gives part 1, and `k = 3` part 2.
+///
+/// Neighbouring windows share all but their first and last depth, so rather than summing
+/// anything, this just compares `a[i]` with `a[i + k]`. Only the last `k` depths are kept,
+/// so however long `depths` is, it takes O(k) memory and no allocation beyond that.
+pub fn count_increases<T: PartialOrd>(depths: impl IntoIterator<Item = T>, k: usize) -> usize {
+    if k == 0 {
+        return 0;
+    }
+
+    // the last k depths, with the oldest at `oldest` once it's full
+    let mut window = Vec::with_capacity(k);
+    let mut oldest = 0;
+    let mut increases = 0;
+
+    for depth in depths {
+        if window.len() < k {
+            window.push(depth);
+            continue;
+        }
+
+        if depth > window[oldest] {
+            increases += 1;
+        }
+        window[oldest] = depth;
+        oldest = if oldest + 1 == k { 0 } else { oldest + 1 };
+    }
+
+    increases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    /// Sums every window, then compares neighbours.
+    fn summed(depths: &[u32], k: usize) -> usize {
+        let sums = depths
+            .windows(k)
+            .map(|w| w.iter().sum())
+            .collect::<Vec<u32>>();
+        sums.windows(2).filter(|w| w[0] < w[1]).count()
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(count_increases(EXAMPLE, 1), 7);
+        assert_eq!(count_increases(EXAMPLE, 3), 5);
+    }
+
+    #[test]
+    fn any_window() {
+        let input = include_str!("../../input.txt")
+            .lines()
+            .map(|num| num.parse().unwrap())
+            .collect::<Vec<u32>>();
+
+        for k in 1..=20 {
+            assert_eq!(count_increases(&input, k), summed(&input, k));
+            assert_eq!(count_increases(EXAMPLE, k), summed(&EXAMPLE, k));
+        }
+
+        assert_eq!(count_increases(EXAMPLE, 0), 0);
+        assert_eq!(count_increases(EXAMPLE, 10), 0);
+        assert_eq!(count_increases(Vec::<u32>::new(), 3), 0);
+    }
+}
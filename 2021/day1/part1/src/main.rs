@@ -1,18 +1,9 @@
-#![feature(array_windows)]
+use day1_part1::count_increases;
 
 fn main() {
     let input = include_str!("../../input.txt");
 
-    let nums = input
-        .lines()
-        .map(|num| num.parse().unwrap())
-        .collect::<Vec<u32>>();
+    let depths = input.lines().map(|num| num.parse::<u32>().unwrap());
 
-    let answer = nums
-        .array_windows()
-        .filter(|[a, b]| a < b)
-        .count();
-
-    println!("{}", answer);
+    println!("{}", count_increases(depths, 1));
 }
-
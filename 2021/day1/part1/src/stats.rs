@@ -0,0 +1,178 @@
+//! Statistics over a whole sonar sweep, beyond counting increases.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// The indices of the longest stretch of strictly increasing depths.
+/// The earliest wins a tie, and an empty sweep gives an empty range.
+pub fn longest_increasing_run(depths: &[u32]) -> Range<usize> {
+    let mut longest = 0..depths.len().min(1);
+    let mut start = 0;
+
+    for (i, pair) in depths.windows(2).enumerate() {
+        if pair[0] >= pair[1] {
+            start = i + 1;
+        } else if i + 2 - start > longest.len() {
+            longest = start..i + 2;
+        }
+    }
+
+    longest
+}
+
+/// The index of the depth before the largest drop between neighbours, and the size of the
+/// drop. `None` if the depth never decreases.
+pub fn largest_drop(depths: &[u32]) -> Option<(usize, u32)> {
+    depths
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] > pair[1])
+        .map(|(i, pair)| (i, pair[0] - pair[1]))
+        .fold(None, |largest, (i, drop)| match largest {
+            Some((_, largest_drop)) if largest_drop >= drop => largest,
+            _ => Some((i, drop)),
+        })
+}
+
+/// The mean of every window of `window` depths.
+pub fn moving_average(depths: &[u32], window: usize) -> Vec<f64> {
+    if window == 0 || depths.len() < window {
+        return Vec::new();
+    }
+
+    let mut sum = depths[..window].iter().map(|&d| u64::from(d)).sum::<u64>();
+    let mut averages = Vec::with_capacity(depths.len() - window + 1);
+    averages.push(sum as f64 / window as f64);
+
+    for (&old, &new) in depths.iter().zip(&depths[window..]) {
+        sum = sum - u64::from(old) + u64::from(new);
+        averages.push(sum as f64 / window as f64);
+    }
+
+    averages
+}
+
+/// The median of every window of `window` depths.
+/// For an even window, that's the mean of the middle two.
+///
+/// The window is kept sorted as it slides, so each step is a binary search and a shift of
+/// at most `window` depths.
+pub fn moving_median(depths: &[u32], window: usize) -> Vec<f64> {
+    if window == 0 || depths.len() < window {
+        return Vec::new();
+    }
+
+    let median = |sorted: &[u32]| {
+        let mid = sorted.len() / 2;
+        if sorted.len() % 2 == 1 {
+            f64::from(sorted[mid])
+        } else {
+            (f64::from(sorted[mid - 1]) + f64::from(sorted[mid])) / 2.0
+        }
+    };
+
+    let mut sorted = depths[..window].to_vec();
+    sorted.sort_unstable();
+    let mut medians = Vec::with_capacity(depths.len() - window + 1);
+    medians.push(median(&sorted));
+
+    for (&old, &new) in depths.iter().zip(&depths[window..]) {
+        let at = sorted.binary_search(&old).unwrap();
+        sorted.remove(at);
+        let at = sorted.partition_point(|&d| d < new);
+        sorted.insert(at, new);
+        medians.push(median(&sorted));
+    }
+
+    medians
+}
+
+/// Counts the changes in depth between neighbours, in buckets `bucket` wide.
+/// Each bucket is keyed by its lowest delta, so with a width of 10, a delta of -3 counts
+/// towards the `-10` bucket and one of 3 towards the `0` bucket.
+pub fn delta_histogram(depths: &[u32], bucket: u32) -> BTreeMap<i64, usize> {
+    assert!(bucket > 0, "buckets must be at least 1 wide");
+    let bucket = i64::from(bucket);
+
+    let mut histogram = BTreeMap::new();
+    for pair in depths.windows(2) {
+        let delta = i64::from(pair[1]) - i64::from(pair[0]);
+        *histogram
+            .entry(delta.div_euclid(bucket) * bucket)
+            .or_insert(0) += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn runs_and_drops() {
+        assert_eq!(longest_increasing_run(&EXAMPLE), 0..4);
+        assert_eq!(longest_increasing_run(&[1, 2, 0, 1, 2, 3]), 2..6);
+        assert_eq!(longest_increasing_run(&[3, 2, 1]), 0..1);
+        assert_eq!(longest_increasing_run(&[1, 2, 2, 3]), 0..2);
+        assert_eq!(longest_increasing_run(&[]), 0..0);
+
+        assert_eq!(largest_drop(&EXAMPLE), Some((3, 10)));
+        assert_eq!(largest_drop(&[1, 2, 3]), None);
+        assert_eq!(largest_drop(&[]), None);
+    }
+
+    #[test]
+    fn moving() {
+        assert_eq!(
+            moving_average(&EXAMPLE, 3),
+            [607.0, 618.0, 618.0, 617.0, 647.0, 716.0, 769.0, 792.0].map(|s| s / 3.0)
+        );
+        assert_eq!(
+            moving_median(&EXAMPLE, 3),
+            [200.0, 208.0, 208.0, 207.0, 207.0, 240.0, 260.0, 263.0]
+        );
+        assert_eq!(
+            moving_median(&EXAMPLE, 4),
+            [204.0, 204.0, 207.5, 208.5, 223.5, 250.0, 261.5]
+        );
+        assert_eq!(moving_median(&EXAMPLE, 1), EXAMPLE.map(f64::from));
+        assert!(moving_average(&EXAMPLE, 11).is_empty());
+        assert!(moving_median(&EXAMPLE, 0).is_empty());
+    }
+
+    #[test]
+    fn medians_match_sorting() {
+        let depths = (0u32..500)
+            .map(|i| i.wrapping_mul(0x9E37_79B9) >> 22)
+            .collect::<Vec<_>>();
+
+        for window in [2, 5, 16] {
+            let expected = depths
+                .windows(window)
+                .map(|w| {
+                    let mut w = w.to_vec();
+                    w.sort_unstable();
+                    let mid = window / 2;
+                    match window % 2 {
+                        1 => f64::from(w[mid]),
+                        _ => (f64::from(w[mid - 1]) + f64::from(w[mid])) / 2.0,
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(moving_median(&depths, window), expected);
+        }
+    }
+
+    #[test]
+    fn histogram() {
+        // deltas of 1, 8, 2, -10, 7, 33, 29, -9, 3
+        let histogram = delta_histogram(&EXAMPLE, 10);
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            [(-10, 2), (0, 5), (20, 1), (30, 1)]
+        );
+        assert_eq!(delta_histogram(&EXAMPLE, 1).values().sum::<usize>(), 9);
+    }
+}
@@ -1,4 +1,7 @@
-#![feature(array_windows)]
+use day1_part1::{count_increases, stats};
+
+/// Columns for the longest bar of the histogram.
+const BAR_WIDTH: usize = 50;
 
 fn main() {
     let input = include_str!("../../input.txt");
@@ -8,16 +11,58 @@ fn main() {
         .filter_map(|num| num.parse().ok())
         .collect::<Vec<u32>>();
 
-    let windows = depths
-        .array_windows::<3>()
-        .map(|a| a.iter().sum())
-        .collect::<Vec<u32>>();
+    // the puzzle's window is 3, but any other can be given as the first argument,
+    // or after `--stats` to print statistics instead of the answer
+    let mut args = std::env::args().skip(1).peekable();
+    let stats = args.next_if(|arg| arg == "--stats").is_some();
+    let k = args
+        .next()
+        .map(|k| k.parse().expect("window size should be a number"))
+        .unwrap_or(3);
+
+    if stats {
+        print_stats(&depths, k);
+    } else {
+        println!("{}", count_increases(&depths, k));
+    }
+}
+
+fn print_stats(depths: &[u32], window: usize) {
+    println!("depths: {}", depths.len());
+    println!(
+        "increases over {}: {}",
+        window,
+        count_increases(depths, window)
+    );
+
+    let run = stats::longest_increasing_run(depths);
+    println!(
+        "longest increasing run: {} depths, from index {}",
+        run.len(),
+        run.start
+    );
 
-    let answer = windows
-        .array_windows()
-        .filter(|[a, b]| a < b)
-        .count();
+    match stats::largest_drop(depths) {
+        Some((i, drop)) => println!("largest drop: {} after index {}", drop, i),
+        None => println!("largest drop: none"),
+    }
 
-    println!("{}", answer);
+    let averages = stats::moving_average(depths, window);
+    let medians = stats::moving_median(depths, window);
+    println!("moving average and median over {}:", window);
+    for (i, (average, median)) in averages.iter().zip(&medians).enumerate() {
+        println!("{:>6} {:>10.2} {:>10.1}", i, average, median);
+    }
 
+    // ten buckets or so is enough to see the shape
+    let deltas = stats::delta_histogram(depths, 1);
+    let spread = deltas.keys().last().unwrap_or(&0) - deltas.keys().next().unwrap_or(&0);
+    let bucket = (spread / 10).max(1) as u32;
+    let histogram = stats::delta_histogram(depths, bucket);
+    let most = histogram.values().copied().max().unwrap_or(1);
+    println!("depth deltas, in buckets of {}:", bucket);
+    for (start, count) in histogram {
+        let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+        println!("{:>6} {:>6} {}", start, count, bar);
+    }
 }
@@ -1,9 +1,8 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day16_part2::generator::{GenConfig, Generator};
 use day16_part2::{nibble::Nibble, packet};
 
-fn bench_compute() -> usize {
-    let input = include_str!("../../input.txt");
-
+fn compute(input: &str) -> usize {
     let bits = input
         .trim()
         .bytes()
@@ -13,9 +12,32 @@ fn bench_compute() -> usize {
     packet::solve(bits).unwrap()
 }
 
+fn bench_compute() -> usize {
+    compute(include_str!("../../input.txt"))
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("test", |b| b.iter(|| bench_compute()));
+    c.bench_function("test", |b| b.iter(bench_compute));
+}
+
+pub fn generated_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated");
+    for max_depth in [4, 6, 8, 10] {
+        let config = GenConfig {
+            max_depth,
+            max_fan_out: 5,
+            literal_percent: 10,
+            ..GenConfig::default()
+        };
+        let transmission = Generator::new(0xD16, config).generate();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(transmission.hex.len()),
+            &transmission.hex,
+            |b, hex| b.iter(|| compute(black_box(hex))),
+        );
+    }
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, criterion_benchmark, generated_benchmark);
 criterion_main!(benches);
@@ -0,0 +1,380 @@
+//! Seeded generation of random, valid packet transmissions.
+//!
+//! The puzzle only gives us one input, which is far too small to say anything about how the
+//! decoder scales. A [Generator] produces arbitrarily large transmissions, along with the value
+//! and version sum they are expected to decode to.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const VERSION_FIELD_SIZE: usize = 3;
+const TYPE_ID_FIELD_SIZE: usize = 3;
+const T0_LEN_FIELD_SIZE: usize = 15;
+const T1_LEN_FIELD_SIZE: usize = 11;
+
+const T0_MAX_LEN: usize = (1 << T0_LEN_FIELD_SIZE) - 1;
+const T1_MAX_LEN: usize = (1 << T1_LEN_FIELD_SIZE) - 1;
+
+const SUM: usize = 0;
+const PRODUCT: usize = 1;
+const MINIMUM: usize = 2;
+const MAXIMUM: usize = 3;
+const LITERAL: usize = 4;
+const GREATER: usize = 5;
+const LESS: usize = 6;
+const EQUAL: usize = 7;
+
+const OPERATORS: [usize; 7] = [SUM, PRODUCT, MINIMUM, MAXIMUM, GREATER, LESS, EQUAL];
+
+/// Controls the shape of the transmissions made by a [Generator].
+#[derive(Clone, Debug)]
+pub struct GenConfig {
+    /// Operator packets nested this deep will only contain literals.
+    pub max_depth: u32,
+    /// Largest number of subpackets in a single operator packet.
+    /// Comparisons always have exactly two.
+    pub max_fan_out: u16,
+    /// Largest number of 4 bit groups in a single literal.
+    /// Capped so that every literal fits in a [usize].
+    pub max_literal_groups: u8,
+    /// Chance (in percent) that an operator packet uses length type 1 rather than type 0.
+    pub t1_percent: u8,
+    /// Chance (in percent) that a packet above `max_depth` is a literal anyway.
+    pub literal_percent: u8,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        GenConfig {
+            max_depth: 6,
+            max_fan_out: 4,
+            max_literal_groups: 4,
+            t1_percent: 50,
+            literal_percent: 25,
+        }
+    }
+}
+
+/// A generated transmission and what it should decode to.
+#[derive(Clone, Debug)]
+pub struct Transmission {
+    /// The transmission, as uppercase hexadecimal ASCII.
+    pub hex: String,
+    /// The value of the outermost packet.
+    pub value: usize,
+    /// The sum of the versions of every packet.
+    pub version_sum: usize,
+}
+
+/// Produces random transmissions.
+/// The same seed and [GenConfig] always produce the same sequence of transmissions.
+pub struct Generator {
+    rng: SplitMix64,
+    config: GenConfig,
+}
+
+impl Generator {
+    #[inline]
+    pub fn new(seed: u64, config: GenConfig) -> Self {
+        Generator {
+            rng: SplitMix64(seed),
+            config,
+        }
+    }
+
+    /// Returns a new random transmission.
+    pub fn generate(&mut self) -> Transmission {
+        let mut bits = Vec::new();
+        let (value, version_sum) = self.packet(&mut bits, 0);
+
+        Transmission {
+            hex: to_hex(&bits),
+            value,
+            version_sum,
+        }
+    }
+
+    /// Appends a packet to `bits`, returning its value and version sum.
+    fn packet(&mut self, bits: &mut Vec<bool>, depth: u32) -> (usize, usize) {
+        let version = self.rng.below(8);
+        push_bits(bits, version, VERSION_FIELD_SIZE);
+
+        if depth >= self.config.max_depth || self.rng.chance(self.config.literal_percent) {
+            push_bits(bits, LITERAL, TYPE_ID_FIELD_SIZE);
+            return (self.literal(bits), version);
+        }
+
+        let op = OPERATORS[self.rng.below(OPERATORS.len())];
+        let num_packets = match op {
+            GREATER | LESS | EQUAL => 2,
+            _ => {
+                1 + self
+                    .rng
+                    .below((self.config.max_fan_out as usize).clamp(1, T1_MAX_LEN))
+            }
+        };
+
+        // the operation and length are only known for sure once the subpackets are written,
+        // so leave space for them and fill them in afterwards
+        let type_id_at = bits.len();
+        push_bits(bits, op, TYPE_ID_FIELD_SIZE);
+        let length_type = self.rng.chance(self.config.t1_percent);
+        bits.push(length_type);
+        let length_at = bits.len();
+        let length_size = match length_type {
+            false => T0_LEN_FIELD_SIZE,
+            true => T1_LEN_FIELD_SIZE,
+        };
+        push_bits(bits, 0, length_size);
+
+        let subpackets_at = bits.len();
+        let mut values = Vec::with_capacity(num_packets);
+        let mut version_sum = version;
+        for _ in 0..num_packets {
+            let (value, versions) = self.packet(bits, depth + 1);
+            values.push(value);
+            version_sum += versions;
+        }
+
+        let (op, value) = evaluate(op, &values);
+        write_bits(bits, type_id_at, op, TYPE_ID_FIELD_SIZE);
+
+        let num_bits = bits.len() - subpackets_at;
+        if length_type {
+            write_bits(bits, length_at, num_packets, T1_LEN_FIELD_SIZE);
+        } else if num_bits <= T0_MAX_LEN {
+            write_bits(bits, length_at, num_bits, T0_LEN_FIELD_SIZE);
+        } else {
+            // too many bits for a type 0 length, so switch to type 1 and close the gap
+            let gap = T0_LEN_FIELD_SIZE - T1_LEN_FIELD_SIZE;
+            bits[length_at - 1] = true;
+            bits.copy_within(subpackets_at.., subpackets_at - gap);
+            bits.truncate(bits.len() - gap);
+            write_bits(bits, length_at, num_packets, T1_LEN_FIELD_SIZE);
+        }
+
+        (value, version_sum)
+    }
+
+    /// Appends a literal body to `bits`, returning its value.
+    fn literal(&mut self, bits: &mut Vec<bool>) -> usize {
+        const GROUP_SIZE: usize = 4;
+        const MAX_GROUPS: usize = usize::BITS as usize / GROUP_SIZE;
+
+        let num_groups = 1 + self
+            .rng
+            .below((self.config.max_literal_groups as usize).clamp(1, MAX_GROUPS));
+
+        let mut value = 0;
+        for group in 0..num_groups {
+            let frag = self.rng.below(1 << GROUP_SIZE);
+            bits.push(group + 1 != num_groups);
+            push_bits(bits, frag, GROUP_SIZE);
+            value = (value << GROUP_SIZE) | frag;
+        }
+        value
+    }
+}
+
+/// Returns the operation that should actually be used, and the value it produces.
+/// Sums and products which would overflow fall back to a maximum and minimum respectively.
+fn evaluate(op: usize, values: &[usize]) -> (usize, usize) {
+    let max = || *values.iter().max().unwrap();
+    let min = || *values.iter().min().unwrap();
+    match op {
+        SUM => values
+            .iter()
+            .try_fold(0usize, |acc, &v| acc.checked_add(v))
+            .map_or((MAXIMUM, max()), |v| (SUM, v)),
+        PRODUCT => values
+            .iter()
+            .try_fold(1usize, |acc, &v| acc.checked_mul(v))
+            .map_or((MINIMUM, min()), |v| (PRODUCT, v)),
+        MINIMUM => (MINIMUM, min()),
+        MAXIMUM => (MAXIMUM, max()),
+        GREATER => (GREATER, (values[0] > values[1]) as usize),
+        LESS => (LESS, (values[0] < values[1]) as usize),
+        EQUAL => (EQUAL, (values[0] == values[1]) as usize),
+        _ => unreachable!("literals are not operators"),
+    }
+}
+
+/// Appends the `n` least significant bits of `value` from MSB to LSB.
+#[inline]
+fn push_bits(bits: &mut Vec<bool>, value: usize, n: usize) {
+    bits.extend((0..n).rev().map(|i| (value >> i) & 1 != 0));
+}
+
+/// Overwrites `n` bits starting at `at` with the `n` least significant bits of `value`.
+#[inline]
+fn write_bits(bits: &mut [bool], at: usize, value: usize, n: usize) {
+    for (i, bit) in bits[at..at + n].iter_mut().enumerate() {
+        *bit = (value >> (n - 1 - i)) & 1 != 0;
+    }
+}
+
+/// Packs bits into hexadecimal ASCII, padding the final nibble with zeros.
+fn to_hex(bits: &[bool]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    bits.chunks(4)
+        .map(|nibble| {
+            let n = nibble
+                .iter()
+                .chain([false; 4].iter())
+                .take(4)
+                .fold(0, |acc, &b| (acc << 1) | b as usize);
+            DIGITS[n] as char
+        })
+        .collect()
+}
+
+/// A tiny, dependency free PRNG. Plenty random enough for making test inputs.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..n`.
+    #[inline]
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    #[inline]
+    fn chance(&mut self, percent: u8) -> bool {
+        self.below(100) < percent as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nibble::Nibble;
+    use crate::packet;
+
+    fn decode(hex: &str) -> usize {
+        let bits = hex
+            .bytes()
+            .map(|b| Nibble::from_hex_ascii(b).unwrap())
+            .flat_map(Nibble::into_bits);
+        packet::solve(bits).unwrap()
+    }
+
+    fn hex_bits(hex: &str) -> Vec<bool> {
+        hex.bytes()
+            .map(|b| Nibble::from_hex_ascii(b).unwrap())
+            .flat_map(Nibble::into_bits)
+            .collect()
+    }
+
+    /// Reads `n` bits starting at `*at` as a number, moving `at` past them.
+    fn read(bits: &[bool], at: &mut usize, n: usize) -> usize {
+        let value = bits[*at..*at + n]
+            .iter()
+            .fold(0, |acc, &b| (acc << 1) | b as usize);
+        *at += n;
+        value
+    }
+
+    /// Walks the packet starting at `*at` without evaluating it, summing its versions.
+    fn walk_versions(bits: &[bool], at: &mut usize) -> usize {
+        let version = read(bits, at, VERSION_FIELD_SIZE);
+        if read(bits, at, TYPE_ID_FIELD_SIZE) == LITERAL {
+            while read(bits, at, 5) & 0b10000 != 0 {}
+            return version;
+        }
+        let mut sum = version;
+        if read(bits, at, 1) == 0 {
+            let len = read(bits, at, T0_LEN_FIELD_SIZE);
+            let end = *at + len;
+            while *at < end {
+                sum += walk_versions(bits, at);
+            }
+            assert_eq!(*at, end, "type 0 length doesn't match its subpackets");
+        } else {
+            for _ in 0..read(bits, at, T1_LEN_FIELD_SIZE) {
+                sum += walk_versions(bits, at);
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn generated_values_decode() {
+        for seed in 0..64 {
+            let t = Generator::new(seed, GenConfig::default()).generate();
+            assert_eq!(decode(&t.hex), t.value, "seed {seed}: {}", t.hex);
+        }
+    }
+
+    #[test]
+    fn long_type_0_falls_back_to_type_1() {
+        let config = GenConfig {
+            max_depth: 3,
+            max_fan_out: 40,
+            max_literal_groups: 16,
+            t1_percent: 0,
+            literal_percent: 0,
+        };
+        let t = Generator::new(7, config).generate();
+        assert!(t.hex.len() * 4 > T0_MAX_LEN);
+        assert_eq!(decode(&t.hex), t.value);
+
+        // every operator asked for type 0, so the root's length type bit was flipped to 1
+        let bits = hex_bits(&t.hex);
+        assert!(bits[VERSION_FIELD_SIZE + TYPE_ID_FIELD_SIZE]);
+        assert_eq!(walk_versions(&bits, &mut 0), t.version_sum);
+    }
+
+    #[test]
+    fn version_sums_match_packets() {
+        let configs = [
+            GenConfig::default(),
+            GenConfig {
+                max_depth: 3,
+                max_fan_out: 40,
+                max_literal_groups: 16,
+                t1_percent: 0,
+                literal_percent: 0,
+            },
+        ];
+        for config in &configs {
+            for seed in 0..64 {
+                let t = Generator::new(seed, config.clone()).generate();
+                let bits = hex_bits(&t.hex);
+                let mut at = 0;
+                assert_eq!(walk_versions(&bits, &mut at), t.version_sum, "seed {seed}");
+                // only padding is left over
+                assert!(bits.len() - at < 4 && !bits[at..].contains(&true));
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_transmission() {
+        let a = Generator::new(42, GenConfig::default()).generate();
+        let b = Generator::new(42, GenConfig::default()).generate();
+        assert_eq!(a.hex, b.hex);
+        assert_eq!(a.version_sum, b.version_sum);
+    }
+
+    #[test]
+    fn single_literal() {
+        let config = GenConfig {
+            max_depth: 0,
+            ..GenConfig::default()
+        };
+        let t = Generator::new(3, config).generate();
+        // a lone literal: a 6 bit header and at most 4 groups of 5 bits
+        assert!(t.hex.len() <= (6 + 4 * 5usize).div_ceil(4));
+        assert!(t.version_sum < 8);
+        assert_eq!(decode(&t.hex), t.value);
+    }
+}
@@ -1,5 +1,8 @@
 #![no_std]
 
+extern crate alloc;
+
+pub mod generator;
 pub mod nibble;
 pub mod packet;
 pub mod util;
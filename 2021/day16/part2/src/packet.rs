@@ -1,33 +1,105 @@
 //! Logic for parsing and computing the "packet" language in day 16 of Advent of Code 2021
 
-use crate::util::{CountIter, Countable, FromBits};
+use crate::util::{CountIter, Countable, Fencable, FromBits};
 use core::cmp;
 use core::ops::{Add, Mul};
 
 pub fn solve(bits: impl Iterator<Item = bool>) -> Result<usize, ComputeError> {
-    compute(&mut bits.counted())
+    compute(&mut bits.counted(), Eval::Strict)
+}
+
+/// Like [solve], but stops evaluating an operator as soon as its result is known.
+///
+/// A product or minimum which reaches 0, or a maximum which reaches [usize::MAX], skips the
+/// rest of its subpackets without evaluating them.
+pub fn solve_lazy(bits: impl Iterator<Item = bool>) -> Result<usize, ComputeError> {
+    compute(&mut bits.counted(), Eval::Lazy)
+}
+
+/// Returns whether the outermost packet, which must be a comparison, holds.
+/// Its operands are evaluated as in [solve_lazy].
+pub fn solve_predicate(bits: impl Iterator<Item = bool>) -> Result<bool, ComputeError> {
+    let bits = &mut bits.counted();
+    let (_version, operation) = get_header(bits)?;
+
+    use Operation as Op;
+    let f = match operation {
+        Op::Greater => |a, b| a > b,
+        Op::Less => |a, b| a < b,
+        Op::Equal => |a, b| a == b,
+        _ => return Err("Expected a comparison packet"),
+    };
+
+    compare(f, bits, Eval::Lazy).map(|result| result != 0)
+}
+
+/// Returns the value of a single packet nested inside the outermost one.
+///
+/// `path` holds the index of the subpacket to descend into at each level, so an empty path
+/// evaluates the outermost packet. Earlier siblings are skipped over without being evaluated,
+/// and nothing after the chosen packet is read.
+pub fn solve_at(bits: impl Iterator<Item = bool>, path: &[usize]) -> Result<usize, ComputeError> {
+    let bits = &mut bits.counted();
+
+    for &index in path {
+        let (_version, operation) = get_header(bits)?;
+        if let Operation::Literal = operation {
+            return Err("Path continues past a literal packet");
+        }
+        skip_to_subpacket(index, bits)?;
+    }
+
+    compute(bits, Eval::Lazy)
+}
+
+/// Consumes the next packet without evaluating it, returning how many bits it took up.
+///
+/// Operators with a type 0 length are jumped over in one go, without parsing their subpackets.
+pub fn skip<I>(bits: &mut I) -> Result<usize, ComputeError>
+where
+    I: Iterator<Item = bool>,
+{
+    let mut bits = bits.counted();
+    skip_packet(&mut bits)?;
+    Ok(bits.iter_count())
+}
+
+/// How thoroughly operators evaluate their subpackets.
+#[derive(Clone, Copy)]
+enum Eval {
+    /// Every subpacket is evaluated.
+    Strict,
+    /// Remaining subpackets are skipped once an operator's result can't change.
+    Lazy,
 }
 
 /// Returns either the solution to the given packet or an error.
 ///
 /// # Arguments
 /// * `bits` - a mutable reference to an [Iterator] over the bits of the packet.
-fn compute<I>(bits: &mut CountIter<I>) -> Result<usize, ComputeError>
+/// * `eval` - whether operators may stop early, see [Eval].
+fn compute<I>(bits: &mut CountIter<I>, eval: Eval) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
 {
     let (_version, operation) = get_header(bits)?;
 
+    // an operator may stop once its accumulator reaches this value
+    let absorbing = |value| match eval {
+        Eval::Strict => None,
+        Eval::Lazy => Some(value),
+    };
+
     use Operation as Op;
     match operation {
-        Op::Sum => reduce(Add::add, bits),
-        Op::Product => reduce(Mul::mul, bits),
-        Op::Minimum => reduce(cmp::min, bits),
-        Op::Maximim => reduce(cmp::max, bits),
+        Op::Sum => reduce(Add::add, None, bits, eval),
+        Op::Product => reduce(Mul::mul, absorbing(0), bits, eval),
+        Op::Minimum => reduce(cmp::min, absorbing(0), bits, eval),
+        Op::Maximim => reduce(cmp::max, absorbing(usize::MAX), bits, eval),
         Op::Literal => literal(bits),
-        Op::Greater => compare(|a, b| a > b, bits),
-        Op::Less => compare(|a, b| a < b, bits),
-        Op::Equal => compare(|a, b| a == b, bits),
+        Op::Greater => compare(|a, b| a > b, bits, eval),
+        Op::Less => compare(|a, b| a < b, bits, eval),
+        Op::Equal => compare(|a, b| a == b, bits, eval),
     }
 }
 
@@ -39,20 +111,27 @@ where
 }
 
 #[inline]
-fn reduce<I>(f: fn(usize, usize) -> usize, bits: &mut CountIter<I>) -> Result<usize, ComputeError>
+fn reduce<I>(
+    f: fn(usize, usize) -> usize,
+    absorbing: Option<usize>,
+    bits: &mut CountIter<I>,
+    eval: Eval,
+) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
 {
     let length_type_id = get_length_type(bits)?;
     match length_type_id {
-        false => reduce_t0(f, bits),
-        true => reduce_t1(f, bits),
+        false => reduce_t0(f, absorbing, bits, eval),
+        true => reduce_t1(f, absorbing, bits, eval),
     }
 }
 
 fn reduce_t0<I>(
     f: fn(usize, usize) -> usize,
+    absorbing: Option<usize>,
     bits: &mut CountIter<I>,
+    eval: Eval,
 ) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
@@ -61,10 +140,17 @@ where
 
     let final_bits_read = bits.iter_count() + num_bits as usize;
 
-    let mut accum = compute(bits)?;
+    let mut accum = compute(bits, eval)?;
 
     while bits.iter_count() != final_bits_read {
-        let subpacket = compute(bits)?;
+        if Some(accum) == absorbing {
+            let remaining = final_bits_read
+                .checked_sub(bits.iter_count())
+                .ok_or("Subpackets overran their type 0 length")?;
+            skip_bits(bits, remaining)?;
+            break;
+        }
+        let subpacket = compute(bits, eval)?;
         accum = f(accum, subpacket);
     }
 
@@ -73,18 +159,26 @@ where
 
 fn reduce_t1<I>(
     f: fn(usize, usize) -> usize,
+    absorbing: Option<usize>,
     bits: &mut CountIter<I>,
+    eval: Eval,
 ) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
 {
     let num_packets = get_length_t1(bits)?;
 
-    let mut accum = compute(bits)?;
+    let mut accum = compute(bits, eval)?;
     let num_packets = num_packets - 1;
 
-    for _ in 0..num_packets {
-        let subpacket = compute(bits)?;
+    for read in 0..num_packets {
+        if Some(accum) == absorbing {
+            for _ in read..num_packets {
+                skip_packet(bits)?;
+            }
+            break;
+        }
+        let subpacket = compute(bits, eval)?;
         accum = f(accum, subpacket);
     }
 
@@ -92,20 +186,25 @@ where
 }
 
 #[inline]
-fn compare<I>(f: fn(usize, usize) -> bool, bits: &mut CountIter<I>) -> Result<usize, ComputeError>
+fn compare<I>(
+    f: fn(usize, usize) -> bool,
+    bits: &mut CountIter<I>,
+    eval: Eval,
+) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
 {
     let length_type_id = get_length_type(bits)?;
     match length_type_id {
-        false => compare_t0(f, bits),
-        true => compare_t1(f, bits),
+        false => compare_t0(f, bits, eval),
+        true => compare_t1(f, bits, eval),
     }
 }
 
 fn compare_t0<I>(
     f: fn(usize, usize) -> bool,
     bits: &mut CountIter<I>,
+    eval: Eval,
 ) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
@@ -114,8 +213,8 @@ where
 
     let bits_read = bits.iter_count();
 
-    let first = compute(bits)?;
-    let second = compute(bits)?;
+    let first = compute(bits, eval)?;
+    let second = compute(bits, eval)?;
 
     let packet_bits_read = bits.iter_count() - bits_read;
 
@@ -129,6 +228,7 @@ where
 fn compare_t1<I>(
     f: fn(usize, usize) -> bool,
     bits: &mut CountIter<I>,
+    eval: Eval,
 ) -> Result<usize, ComputeError>
 where
     I: Iterator<Item = bool>,
@@ -137,13 +237,93 @@ where
     if num_packets != 2 {
         Err("Comparison operation length field did not match exactly two subpackets")
     } else {
-        let first = compute(bits)?;
-        let second = compute(bits)?;
+        let first = compute(bits, eval)?;
+        let second = compute(bits, eval)?;
 
         Ok(f(first, second) as usize)
     }
 }
 
+fn skip_packet<I>(bits: &mut CountIter<I>) -> Result<(), ComputeError>
+where
+    I: Iterator<Item = bool>,
+{
+    let (_version, operation) = get_header(bits)?;
+
+    match operation {
+        Operation::Literal => skip_literal(bits),
+        _ => match get_length_type(bits)? {
+            false => {
+                let num_bits = get_length_t0(bits)?;
+                skip_bits(bits, num_bits as usize)
+            }
+            true => {
+                let num_packets = get_length_t1(bits)?;
+                for _ in 0..num_packets {
+                    skip_packet(bits)?;
+                }
+                Ok(())
+            }
+        },
+    }
+}
+
+fn skip_literal<I>(bits: &mut CountIter<I>) -> Result<(), ComputeError>
+where
+    I: Iterator<Item = bool>,
+{
+    const GROUP_SIZE: usize = 5;
+    loop {
+        let group: u8 = try_read_bits(bits, GROUP_SIZE)
+            .ok_or("Expected literal value, but bit stream ended")?;
+        if group & 0b10000 == 0 {
+            break Ok(());
+        }
+    }
+}
+
+#[inline]
+fn skip_bits<I>(bits: &mut CountIter<I>, n: usize) -> Result<(), ComputeError>
+where
+    I: Iterator<Item = bool>,
+{
+    if bits.fence(n).count() == n {
+        Ok(())
+    } else {
+        Err("Expected subpackets, but bit stream ended")
+    }
+}
+
+/// Positions `bits` at the start of subpacket `index` of an operator whose header was just read.
+fn skip_to_subpacket<I>(index: usize, bits: &mut CountIter<I>) -> Result<(), ComputeError>
+where
+    I: Iterator<Item = bool>,
+{
+    const OUT_OF_RANGE: ComputeError = "Path index is past the last subpacket";
+    match get_length_type(bits)? {
+        false => {
+            let num_bits = get_length_t0(bits)?;
+            let final_bits_read = bits.iter_count() + num_bits as usize;
+            for _ in 0..index {
+                skip_packet(bits)?;
+            }
+            if bits.iter_count() >= final_bits_read {
+                return Err(OUT_OF_RANGE);
+            }
+        }
+        true => {
+            let num_packets = get_length_t1(bits)?;
+            if index >= num_packets as usize {
+                return Err(OUT_OF_RANGE);
+            }
+            for _ in 0..index {
+                skip_packet(bits)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[inline]
 fn get_header<I>(bits: &mut CountIter<I>) -> Result<(u8, Operation), ComputeError>
 where
@@ -278,3 +458,70 @@ where
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{GenConfig, Generator};
+    use crate::nibble::Nibble;
+
+    fn bits(hex: &str) -> impl Iterator<Item = bool> + '_ {
+        hex.bytes()
+            .map(|b| Nibble::from_hex_ascii(b).unwrap())
+            .flat_map(Nibble::into_bits)
+    }
+
+    #[test]
+    fn lazy_matches_strict() {
+        for seed in 0..64 {
+            let t = Generator::new(seed, GenConfig::default()).generate();
+            assert_eq!(solve_lazy(bits(&t.hex)), Ok(t.value), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn lazy_skips_after_zero() {
+        // product of 0 and an equality with three operands, using a type 0 length
+        // the strict evaluation rejects the equality, the lazy one jumps straight over it
+        let hex = "040074400F006";
+        assert!(solve(bits(hex)).is_err());
+        assert_eq!(solve_lazy(bits(hex)), Ok(0));
+    }
+
+    #[test]
+    fn predicate() {
+        assert_eq!(
+            solve_predicate(bits("9C0141080250320F1802104A08")),
+            Ok(true)
+        );
+        assert_eq!(solve_predicate(bits("9C005AC2F8F0")), Ok(false));
+        assert_eq!(solve_predicate(bits("F600BC2D8F")), Ok(false));
+        assert_eq!(solve_predicate(bits("D8005AC2A8F0")), Ok(true));
+        assert!(solve_predicate(bits("C200B40A82")).is_err());
+    }
+
+    #[test]
+    fn at_path() {
+        // sum of 1 and 2
+        assert_eq!(solve_at(bits("C200B40A82"), &[]), Ok(3));
+        assert_eq!(solve_at(bits("C200B40A82"), &[0]), Ok(1));
+        assert_eq!(solve_at(bits("C200B40A82"), &[1]), Ok(2));
+        assert!(solve_at(bits("C200B40A82"), &[2]).is_err());
+        assert!(solve_at(bits("C200B40A82"), &[0, 0]).is_err());
+        // 1 + 3 == 2 * 2, with type 0 lengths
+        let hex = "9C0141080250320F1802104A08";
+        assert_eq!(solve_at(bits(hex), &[0]), Ok(4));
+        assert_eq!(solve_at(bits(hex), &[1, 1]), Ok(2));
+        assert!(solve_at(bits(hex), &[2]).is_err());
+    }
+
+    #[test]
+    fn skip_whole_packet() {
+        for seed in 0..16 {
+            let t = Generator::new(seed, GenConfig::default()).generate();
+            let mut stream = bits(&t.hex);
+            let skipped = skip(&mut stream).unwrap();
+            assert!(stream.all(|b| !b), "only padding should remain");
+            assert!(skipped <= t.hex.len() * 4 && skipped + 4 > t.hex.len() * 4);
+        }
+    }
+}
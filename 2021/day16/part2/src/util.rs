@@ -14,11 +14,7 @@ pub trait NextN: Iterator {
         #[allow(clippy::uninit_assumed_init)]
         let mut arr: [Self::Item; N] = unsafe { MaybeUninit::uninit().assume_init() };
         for pos in arr.iter_mut() {
-            if let Some(v) = self.next() {
-                *pos = v;
-            } else {
-                return None;
-            }
+            *pos = self.next()?;
         }
         Some(arr)
     }
@@ -102,7 +98,7 @@ pub struct Fence<'a, I> {
 
 impl<'a, I> Fence<'a, I> {
     #[inline]
-    pub fn new(inner: &'a mut I, limit: usize) -> Fence<I> {
+    pub fn new(inner: &'a mut I, limit: usize) -> Fence<'a, I> {
         Fence { inner, limit }
     }
 
@@ -143,7 +139,7 @@ pub trait Fencable {
     /// Returns an [Iterator] which restricts iteration of the wrapped [Iterator]
     /// Similar to [Iterator::take], but borrows its inner iterator instead of moving
     #[inline]
-    fn fence(&mut self, limit: usize) -> Fence<Self>
+    fn fence(&mut self, limit: usize) -> Fence<'_, Self>
     where
         Self: Sized,
     {
@@ -159,26 +155,11 @@ mod tests {
 
     #[test]
     fn from_bits_test_u8() {
-        let num = u8::from_bits([1, 0, 1, 1, 0, 1, 1, 1].into_iter().map(|b| {
-            if b == 1 {
-                true
-            } else {
-                false
-            }
-        }));
+        let num = u8::from_bits([1, 0, 1, 1, 0, 1, 1, 1].into_iter().map(|b| b == 1));
 
         assert_eq!(num, 0b10110111);
 
-        let num =
-            u8::from_bits([1, 0, 1, 1, 0, 1].into_iter().map(
-                |b| {
-                    if b == 1 {
-                        true
-                    } else {
-                        false
-                    }
-                },
-            ));
+        let num = u8::from_bits([1, 0, 1, 1, 0, 1].into_iter().map(|b| b == 1));
 
         assert_eq!(num, 0b101101);
     }
@@ -188,7 +169,7 @@ mod tests {
         let num = u16::from_bits(
             [1, 0, 1, 1, 0, 1, 1, 1, 0, 1, 0, 1, 0, 1, 1, 1]
                 .into_iter()
-                .map(|b| if b == 1 { true } else { false }),
+                .map(|b| b == 1),
         );
 
         assert_eq!(num, 0b1011011101010111);
@@ -0,0 +1,475 @@
+//! Submarine commands for day 2 of Advent of Code 2021, and the two ways of following them.
+//!
+//! Part 1 reads `down` and `up` as changes in depth, part 2 as changes in aim.
+//! Both share the same [Command] parser, and are driven by the same [run] function.
+//!
+//! Beyond the puzzle's three commands, `back N`, `surface` and `level` are understood too,
+//! and [script] adds `repeat` blocks and comments on top.
+//!
+//! Submarines are generic over their [Coord]inate type, so long generated inputs can use
+//! [i64] or [i128] rather than overflowing an [i32]. [run_checked] reports where that happens.
+
+use std::fmt;
+use std::ops::{Add, Mul, Neg};
+use std::str::FromStr;
+
+pub mod script;
+pub mod trajectory;
+
+/// An integer type that submarine positions can be counted in.
+pub trait Coord:
+    Copy
+    + Default
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + Into<i128>
+    + Add<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            #[inline]
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            #[inline]
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            #[inline]
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+        })*
+    };
+}
+
+impl_coord!(i32, i64, i128);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command<T = i32> {
+    /// `forward N`, or `back N` as a negative thrust.
+    Thrust(T),
+    /// `down N`, or `up N` as a negative pitch.
+    Pitch(T),
+    /// `surface`: straight up to depth 0.
+    Surface,
+    /// `level`: aim 0, for submarines which aim.
+    Level,
+}
+
+impl<T: Coord> FromStr for Command<T> {
+    type Err = CommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "surface" => return Ok(Command::Surface),
+            "level" => return Ok(Command::Level),
+            _ => {}
+        }
+
+        let (dir, dist) = input
+            .split_once(' ')
+            .ok_or_else(|| CommandError::Split(input.to_string()))?;
+        let bad_dist = || CommandError::Distance(dist.to_string());
+        let dist: T = dist.parse().map_err(|_| bad_dist())?;
+        match dir {
+            "forward" => Ok(Command::Thrust(dist)),
+            "back" => Ok(Command::Thrust(dist.checked_neg().ok_or_else(bad_dist)?)),
+            "down" => Ok(Command::Pitch(dist)),
+            "up" => Ok(Command::Pitch(dist.checked_neg().ok_or_else(bad_dist)?)),
+            d => Err(CommandError::Direction(d.to_string())),
+        }
+    }
+}
+
+/// Why a [Command] couldn't be parsed, along with the offending token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    /// The whole input, which has no space between direction and distance.
+    Split(String),
+    Direction(String),
+    /// Not a number, or one too big for the coordinate type.
+    Distance(String),
+    /// A `repeat` without a count and an opening brace, or with a bad count.
+    Repeat(String),
+    /// A `repeat` block which is never closed.
+    Unclosed,
+    /// A closing brace without a `repeat` to close.
+    Unopened,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Split(input) => {
+                write!(f, "could not divide direction and distance in {:?}", input)
+            }
+            CommandError::Direction(d) => write!(f, "could not parse direction {:?}", d),
+            CommandError::Distance(d) => write!(f, "could not parse distance {:?}", d),
+            CommandError::Repeat(r) => write!(f, "could not parse repeat {:?}", r),
+            CommandError::Unclosed => write!(f, "repeat block is never closed"),
+            CommandError::Unopened => write!(f, "closing brace without a repeat"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// A [CommandError], and the line (counting from 1) it was found on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub error: CommandError,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// Something which can follow [Command]s.
+pub trait Submarine: Default {
+    type Coord: Coord;
+
+    /// Returns the submarine after following `cmd`.
+    /// Like the arithmetic it does, this panics on overflow in debug builds, and wraps in release.
+    fn step(self, cmd: Command<Self::Coord>) -> Self;
+
+    /// Like [Submarine::step], but returns `None` if any coordinate would overflow.
+    fn checked_step(self, cmd: Command<Self::Coord>) -> Option<Self>;
+
+    fn horizontal(&self) -> Self::Coord;
+
+    fn depth(&self) -> Self::Coord;
+
+    /// The puzzle answer.
+    #[inline]
+    fn position_product(&self) -> Self::Coord {
+        self.horizontal() * self.depth()
+    }
+
+    /// Like [Submarine::position_product], but returns `None` on overflow.
+    #[inline]
+    fn checked_position_product(&self) -> Option<Self::Coord> {
+        self.horizontal().checked_mul(self.depth())
+    }
+}
+
+/// Part 1: `down` and `up` move the submarine directly. With nothing to aim, `level` does nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Naive<T = i32> {
+    pub horiz: T,
+    pub depth: T,
+}
+
+impl<T: Coord> Submarine for Naive<T> {
+    type Coord = T;
+
+    #[inline]
+    fn step(self, cmd: Command<T>) -> Self {
+        let Naive { horiz, depth } = self;
+        match cmd {
+            Command::Thrust(dist) => Naive {
+                horiz: horiz + dist,
+                depth,
+            },
+            Command::Pitch(dist) => Naive {
+                horiz,
+                depth: depth + dist,
+            },
+            Command::Surface => Naive {
+                horiz,
+                depth: T::default(),
+            },
+            Command::Level => self,
+        }
+    }
+
+    #[inline]
+    fn checked_step(self, cmd: Command<T>) -> Option<Self> {
+        let Naive { horiz, depth } = self;
+        Some(match cmd {
+            Command::Thrust(dist) => Naive {
+                horiz: horiz.checked_add(dist)?,
+                depth,
+            },
+            Command::Pitch(dist) => Naive {
+                horiz,
+                depth: depth.checked_add(dist)?,
+            },
+            Command::Surface | Command::Level => self.step(cmd),
+        })
+    }
+
+    #[inline]
+    fn horizontal(&self) -> T {
+        self.horiz
+    }
+
+    #[inline]
+    fn depth(&self) -> T {
+        self.depth
+    }
+}
+
+/// Part 2: `down` and `up` tilt the submarine, and moving forward follows the tilt.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Aimed<T = i32> {
+    pub aim: T,
+    pub horiz: T,
+    pub depth: T,
+}
+
+impl<T: Coord> Submarine for Aimed<T> {
+    type Coord = T;
+
+    #[inline]
+    fn step(self, cmd: Command<T>) -> Self {
+        let Aimed { aim, horiz, depth } = self;
+        match cmd {
+            Command::Thrust(dist) => Aimed {
+                aim,
+                horiz: horiz + dist,
+                depth: depth + (aim * dist),
+            },
+            Command::Pitch(tilt) => Aimed {
+                aim: aim + tilt,
+                horiz,
+                depth,
+            },
+            Command::Surface => Aimed {
+                aim,
+                horiz,
+                depth: T::default(),
+            },
+            Command::Level => Aimed {
+                aim: T::default(),
+                horiz,
+                depth,
+            },
+        }
+    }
+
+    #[inline]
+    fn checked_step(self, cmd: Command<T>) -> Option<Self> {
+        let Aimed { aim, horiz, depth } = self;
+        Some(match cmd {
+            Command::Thrust(dist) => Aimed {
+                aim,
+                horiz: horiz.checked_add(dist)?,
+                depth: depth.checked_add(aim.checked_mul(dist)?)?,
+            },
+            Command::Pitch(tilt) => Aimed {
+                aim: aim.checked_add(tilt)?,
+                horiz,
+                depth,
+            },
+            Command::Surface | Command::Level => self.step(cmd),
+        })
+    }
+
+    #[inline]
+    fn horizontal(&self) -> T {
+        self.horiz
+    }
+
+    #[inline]
+    fn depth(&self) -> T {
+        self.depth
+    }
+}
+
+/// Parses every line of `input` into a [Command].
+pub fn commands<T: Coord>(input: &str) -> impl Iterator<Item = Result<Command<T>, LineError>> + '_ {
+    input.lines().enumerate().map(|(line, step)| {
+        step.parse().map_err(|error| LineError {
+            line: line + 1,
+            error,
+        })
+    })
+}
+
+/// Follows every command in `input`, stopping at the first line which doesn't parse.
+pub fn run<S: Submarine>(input: &str) -> Result<S, LineError> {
+    commands(input).try_fold(S::default(), |sub, cmd| Ok(sub.step(cmd?)))
+}
+
+/// Like [run], but also stops at the first command which would overflow a coordinate.
+pub fn run_checked<S: Submarine>(input: &str) -> Result<S, RunError> {
+    commands(input)
+        .enumerate()
+        .try_fold(S::default(), |sub, (step, cmd)| {
+            sub.checked_step(cmd?).ok_or(RunError::Overflow {
+                step: step + 1,
+                line: step + 1,
+            })
+        })
+}
+
+/// Why [run_checked] stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RunError {
+    Parse(LineError),
+    /// Following a command would overflow. `step` counts the commands followed, including
+    /// that one, and `line` is where it came from, both counting from 1. Without repeats,
+    /// they're the same.
+    Overflow {
+        step: usize,
+        line: usize,
+    },
+}
+
+impl From<LineError> for RunError {
+    fn from(err: LineError) -> Self {
+        RunError::Parse(err)
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(err) => err.fmt(f),
+            RunError::Overflow { step, line } if step == line => {
+                write!(f, "line {}: coordinates overflow", line)
+            }
+            RunError::Overflow { step, line } => {
+                write!(f, "line {} (step {}): coordinates overflow", line, step)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+/// Unwraps `result`, or prints the error and exits with status 1, for the binaries.
+pub fn exit_on_error<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| exit_with(err))
+}
+
+/// Prints `message` and exits with status 1, for the binaries.
+pub fn exit_with(message: impl fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1)
+}
+
+/// Exits through [exit_with] if `args` has more than `expected` arguments.
+pub fn expect_args(args: &[String], expected: usize) {
+    if let Some(arg) = args.get(expected) {
+        exit_with(format_args!("unexpected argument {:?}", arg));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+    fn err(line: usize, error: CommandError) -> LineError {
+        LineError { line, error }
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(run::<Naive>(EXAMPLE).unwrap().position_product(), 150);
+        assert_eq!(run::<Aimed>(EXAMPLE).unwrap().position_product(), 900);
+    }
+
+    #[test]
+    fn bad_lines() {
+        assert_eq!(
+            run::<Naive>("forward 5\nsideways 2\n").unwrap_err(),
+            err(2, CommandError::Direction("sideways".to_string()))
+        );
+        assert_eq!(
+            run::<Aimed>("forward 5\ndown 5\nup three\n").unwrap_err(),
+            err(3, CommandError::Distance("three".to_string()))
+        );
+        assert_eq!(
+            run::<Aimed>("forward5\n").unwrap_err(),
+            err(1, CommandError::Split("forward5".to_string()))
+        );
+    }
+
+    #[test]
+    fn extended_commands() {
+        let input = "down 5\nforward 3\nback 1\nlevel\nforward 4\nsurface\ndown 2\n";
+        assert_eq!(run::<Naive>(input).unwrap(), Naive { horiz: 6, depth: 2 });
+        assert_eq!(
+            run::<Aimed>(input).unwrap(),
+            Aimed {
+                aim: 2,
+                horiz: 6,
+                depth: 0
+            }
+        );
+        assert_eq!(
+            run::<Naive>("surface 3\n").unwrap_err(),
+            err(1, CommandError::Direction("surface".to_string()))
+        );
+    }
+
+    #[test]
+    fn wide_distances() {
+        let input = "forward 3000000000\ndown 3000000000\nforward 2\nup 1\n";
+        assert_eq!(
+            run::<Aimed>(input).unwrap_err(),
+            err(1, CommandError::Distance("3000000000".to_string()))
+        );
+
+        let sub = run::<Aimed<i64>>(input).unwrap();
+        assert_eq!(sub.aim, 2_999_999_999);
+        assert_eq!(sub.depth, 6_000_000_000);
+        // which even an i64 can't multiply
+        assert_eq!(sub.checked_position_product(), None);
+
+        let sub = run::<Aimed<i128>>(input).unwrap();
+        assert_eq!(sub.position_product(), 3_000_000_002 * 6_000_000_000);
+
+        assert_eq!(run::<Naive<i128>>(input).unwrap().depth, 2_999_999_999);
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "down 65536\nforward 1\nforward 32768\nforward 1\n";
+        assert_eq!(
+            run_checked::<Aimed>(input),
+            Err(RunError::Overflow { step: 3, line: 3 })
+        );
+        assert_eq!(
+            run_checked::<Aimed<i64>>(input).unwrap().depth,
+            65536 * 32770
+        );
+        assert_eq!(
+            run_checked::<Naive>("down 5\nsideways 2\n"),
+            Err(RunError::Parse(err(
+                2,
+                CommandError::Direction("sideways".to_string())
+            )))
+        );
+        assert_eq!(
+            "up -2147483648".parse::<Command>(),
+            Err(CommandError::Distance("-2147483648".to_string()))
+        );
+
+        let far = run_checked::<Naive>("forward 65536\ndown 65536\n").unwrap();
+        assert_eq!(far.checked_position_product(), None);
+    }
+}
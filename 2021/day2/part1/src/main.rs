@@ -1,30 +1,23 @@
+use day2_part1::script::Script;
+use day2_part1::{exit_on_error, exit_with, expect_args, run_checked, Naive};
+
 fn main() {
     let input = include_str!("../../input.txt");
 
-    let (x, y) = input
-        .lines()
-        .filter_map(|step| step_from_str(step).ok())
-        .reduce(|(a, b), (c, d)| (a + c, b + d))
-        .unwrap();
-
-    println!("{}", x * y);
-
-}
-
-fn step_from_str(input: &str) -> Result<(i32, i32), String> {
-    if let Some((dir, dist)) = input.split_once(' ') {
-        if let Ok(dist) = dist.parse() {
-            match dir {
-                "forward" => Ok((dist, 0)),
-                "down" => Ok((0, dist)),
-                "up" => Ok((0, -dist)),
-                d => Err(format!("Could not parse direction: {}", d))
-            }
-        } else {
-            Err(format!("Could not parse distance: {}", dist))
+    // `--script <path>` follows a scenario script rather than the puzzle input
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let sub = match args.first().map(String::as_str) {
+        Some("--script") => {
+            let path = exit_on_error(args.get(1).ok_or("--script needs a path"));
+            expect_args(&args, 2);
+            let script =
+                exit_on_error(Script::load(path).map_err(|err| format!("{}: {}", path, err)));
+            exit_on_error(script.run_checked::<Naive<i64>>())
         }
-    } else {
-        Err(format!("Could not divide direction and distance"))
-    }
-}
+        Some(arg) => exit_with(format_args!("unknown argument {:?}", arg)),
+        None => exit_on_error(run_checked::<Naive<i64>>(input)),
+    };
 
+    // two i64s always multiply into an i128
+    println!("{}", i128::from(sub.horiz) * i128::from(sub.depth));
+}
@@ -0,0 +1,224 @@
+//! Scenario scripts: one [Command] per line, plus `repeat` blocks and `#` comments.
+//!
+//! ```text
+//! # dive, then zig-zag back up
+//! down 10
+//! repeat 3 {
+//!     forward 5
+//!     up 2
+//! }
+//! surface
+//! ```
+//!
+//! Any puzzle input is also a script, which does the same thing.
+
+use crate::{Command, CommandError, Coord, LineError, RunError, Submarine};
+use std::path::Path;
+use std::{fmt, fs, io};
+
+/// A parsed script, ready to be followed by any number of submarines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Script<T = i32> {
+    body: Vec<Stmt<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Stmt<T> {
+    /// A command, and the line (counting from 1) it came from.
+    Command(usize, Command<T>),
+    Repeat(usize, Vec<Stmt<T>>),
+}
+
+impl<T: Coord> Script<T> {
+    /// Parses a whole script, stopping at the first line that doesn't parse.
+    pub fn parse(input: &str) -> Result<Self, LineError> {
+        // the blocks still open, innermost last, each with the line and count of its `repeat`
+        let mut open: Vec<(usize, usize, Vec<Stmt<T>>)> = Vec::new();
+        let mut body = Vec::new();
+
+        for (line, text) in input.lines().enumerate() {
+            let line = line + 1;
+            let err = |error| LineError { line, error };
+
+            let text = match text.split_once('#') {
+                Some((text, _comment)) => text,
+                None => text,
+            };
+            let text = text.trim();
+
+            if text.is_empty() {
+                continue;
+            } else if text == "}" {
+                let (_, times, outer) = open.pop().ok_or(err(CommandError::Unopened))?;
+                let block = std::mem::replace(&mut body, outer);
+                body.push(Stmt::Repeat(times, block));
+            } else if let Some(header) = text.strip_prefix("repeat ") {
+                let times = header
+                    .strip_suffix('{')
+                    .and_then(|times| times.trim().parse().ok())
+                    .ok_or_else(|| err(CommandError::Repeat(header.to_string())))?;
+                open.push((line, times, std::mem::take(&mut body)));
+            } else {
+                body.push(Stmt::Command(line, text.parse().map_err(err)?));
+            }
+        }
+
+        match open.first() {
+            Some(&(line, _, _)) => Err(LineError {
+                line,
+                error: CommandError::Unclosed,
+            }),
+            None => Ok(Script { body }),
+        }
+    }
+
+    /// Reads and parses the script at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let text = fs::read_to_string(path).map_err(LoadError::Read)?;
+        Script::parse(&text).map_err(LoadError::Parse)
+    }
+
+    /// Every command the script runs, in order and with repeats unrolled, along with the
+    /// line each came from.
+    pub fn commands(&self) -> impl Iterator<Item = (usize, Command<T>)> + '_ {
+        unroll(&self.body)
+    }
+
+    /// Follows the script from the surface.
+    pub fn run<S: Submarine<Coord = T>>(&self) -> S {
+        self.commands()
+            .fold(S::default(), |sub, (_, cmd)| sub.step(cmd))
+    }
+
+    /// Like [Script::run], but stops at the first command which would overflow a coordinate.
+    pub fn run_checked<S: Submarine<Coord = T>>(&self) -> Result<S, RunError> {
+        self.commands()
+            .enumerate()
+            .try_fold(S::default(), |sub, (step, (line, cmd))| {
+                sub.checked_step(cmd).ok_or(RunError::Overflow {
+                    step: step + 1,
+                    line,
+                })
+            })
+    }
+}
+
+/// Why [Script::load] failed.
+#[derive(Debug)]
+pub enum LoadError {
+    Read(io::Error),
+    Parse(LineError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read(err) => err.fmt(f),
+            LoadError::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn unroll<T: Coord>(body: &[Stmt<T>]) -> Box<dyn Iterator<Item = (usize, Command<T>)> + '_> {
+    Box::new(body.iter().flat_map(|stmt| match stmt {
+        &Stmt::Command(line, cmd) => Box::new(std::iter::once((line, cmd))),
+        Stmt::Repeat(times, block) => {
+            Box::new((0..*times).flat_map(move |_| unroll(block))) as Box<dyn Iterator<Item = _>>
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, Aimed, Naive};
+
+    #[test]
+    fn puzzle_input() {
+        let input = include_str!("../../input.txt");
+        let script = Script::parse(input).unwrap();
+        assert_eq!(script.run::<Naive>(), run::<Naive>(input).unwrap());
+        assert_eq!(script.run::<Aimed>(), run::<Aimed>(input).unwrap());
+    }
+
+    #[test]
+    fn repeats() {
+        let script = Script::parse(
+            "# dive, then zig-zag back up\n\
+             down 10\n\
+             repeat 3 {\n\
+             \tforward 5 # full ahead\n\
+             \trepeat 2 {\n\
+             \t\tup 1\n\
+             \t}\n\
+             }\n\
+             \n\
+             level\n\
+             back 1\n",
+        )
+        .unwrap();
+
+        let lines = script.commands().map(|(line, _)| line).collect::<Vec<_>>();
+        assert_eq!(lines, [2, 4, 6, 6, 4, 6, 6, 4, 6, 6, 10, 11]);
+
+        assert_eq!(
+            script.run::<Naive>(),
+            Naive {
+                horiz: 14,
+                depth: 4
+            }
+        );
+        // depth gained at aims of 10, 8 and 6
+        assert_eq!(
+            script.run::<Aimed>(),
+            Aimed {
+                aim: 0,
+                horiz: 14,
+                depth: 120
+            }
+        );
+        assert_eq!(
+            Script::<i32>::parse("repeat 0 {\n}\n")
+                .unwrap()
+                .commands()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = |line, error| Err(LineError { line, error });
+        let parse = Script::<i32>::parse;
+
+        assert_eq!(
+            parse("down 1\nrepeat 2 {\nup 1\n"),
+            err(2, CommandError::Unclosed)
+        );
+        assert_eq!(parse("down 1\n}\n"), err(2, CommandError::Unopened));
+        assert_eq!(
+            parse("repeat twice {\n}\n"),
+            err(1, CommandError::Repeat("twice {".to_string()))
+        );
+        assert_eq!(
+            parse("repeat 2\nup 1\n"),
+            err(1, CommandError::Repeat("2".to_string()))
+        );
+        assert_eq!(
+            parse("# ok\nrepeat 2 {\n  dive 1\n}\n"),
+            err(3, CommandError::Direction("dive".to_string()))
+        );
+
+        // the second time round the loop overflows
+        let script = Script::<i32>::parse("down 65536\n\nrepeat 3 {\nforward 16384\n}\n").unwrap();
+        let overflow = script.run_checked::<Aimed>().unwrap_err();
+        assert_eq!(overflow, RunError::Overflow { step: 3, line: 4 });
+        assert_eq!(
+            overflow.to_string(),
+            "line 4 (step 3): coordinates overflow"
+        );
+        assert!(script.run_checked::<Naive>().is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+//! The whole path a submarine takes, rather than just where it ends up.
+
+use crate::{commands, Aimed, Coord, LineError, Submarine};
+use std::io::{self, Write};
+
+/// Every state of a submarine following `input`, each with the number of commands followed
+/// to get there. Starts with the untouched submarine at step 0, and ends after the first line
+/// which doesn't parse.
+pub fn trajectory<'a, S: Submarine + Copy + 'a>(
+    input: &'a str,
+) -> impl Iterator<Item = Result<(usize, S), LineError>> + 'a {
+    let start = std::iter::once(Ok((0, S::default())));
+    let path = commands(input)
+        .enumerate()
+        .scan(Some(S::default()), |sub, (step, cmd)| {
+            let prev = sub.take()?;
+            Some(cmd.map(|cmd| {
+                let next = prev.step(cmd);
+                *sub = Some(next);
+                (step + 1, next)
+            }))
+        });
+    start.chain(path)
+}
+
+/// Writes `states` as CSV, with a header line.
+pub fn write_csv<W: Write, T: Coord>(
+    mut out: W,
+    states: impl IntoIterator<Item = (usize, Aimed<T>)>,
+) -> io::Result<()> {
+    writeln!(out, "step,aim,horizontal,depth")?;
+    for (step, Aimed { aim, horiz, depth }) in states {
+        writeln!(out, "{},{},{},{}", step, aim, horiz, depth)?;
+    }
+    Ok(())
+}
+
+/// Draws depth against horizontal position, `width` columns by `height` rows, with the
+/// surface at the top. Each column marks the depth at that position with a `#`, and the
+/// water above it with a `.`.
+///
+/// Depth only changes when moving forward, and then linearly, so the depth between two
+/// states is interpolated. That is done in floating point, since differences between even
+/// [i64] coordinates can't be multiplied without overflowing.
+pub fn depth_profile<S: Submarine>(states: &[(usize, S)], width: usize, height: usize) -> String {
+    if states.is_empty() || width == 0 || height == 0 {
+        return String::new();
+    }
+
+    let position = |sub: &S| -> (f64, f64) {
+        let (x, y): (i128, i128) = (sub.horizontal().into(), sub.depth().into());
+        (x as f64, y as f64)
+    };
+    let points = states
+        .iter()
+        .map(|(_, sub)| position(sub))
+        .collect::<Vec<_>>();
+
+    let (min_x, max_x) = bounds(points.iter().map(|&(x, _)| x));
+    let (min_y, max_y) = bounds(points.iter().map(|&(_, y)| y));
+    let min_y = min_y.min(0.0);
+
+    let scale = |v: f64, min: f64, max: f64, cells: usize| -> usize {
+        if max == min {
+            0
+        } else {
+            ((v - min) / (max - min) * (cells - 1) as f64).round() as usize
+        }
+    };
+    let column = |x: f64| scale(x, min_x, max_x, width);
+    let row = |y: f64| scale(y, min_y, max_y, height);
+
+    let mut depths = vec![None; width];
+    for pair in points.windows(2) {
+        let [(xa, ya), (xb, yb)] = [pair[0], pair[1]];
+        let (from, to) = (column(xa), column(xb));
+
+        for (c, depth) in depths
+            .iter_mut()
+            .enumerate()
+            .take(from.max(to) + 1)
+            .skip(from.min(to))
+        {
+            // back to the horizontal position this column stands for, then along the segment
+            let x = min_x + c as f64 * (max_x - min_x) / (width - 1).max(1) as f64;
+            let y = if xa == xb {
+                yb
+            } else {
+                ya + (yb - ya) * (x - xa) / (xb - xa)
+            };
+            *depth = Some(row(y.clamp(min_y, max_y)));
+        }
+    }
+    if let [(x, y)] = points[..] {
+        depths[column(x)] = Some(row(y));
+    }
+
+    let surface = row(0.0);
+    let mut out = String::with_capacity((width + 1) * height);
+    for r in 0..height {
+        out.extend(depths.iter().map(|&d| match d {
+            Some(d) if d == r => '#',
+            Some(d) if (surface..d).contains(&r) => '.',
+            _ => ' ',
+        }));
+        out.push('\n');
+    }
+    out
+}
+
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommandError, Naive};
+
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+    fn aimed(aim: i32, horiz: i32, depth: i32) -> Aimed {
+        Aimed { aim, horiz, depth }
+    }
+
+    #[test]
+    fn example() {
+        let states = trajectory::<Aimed>(EXAMPLE)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            states,
+            [
+                (0, aimed(0, 0, 0)),
+                (1, aimed(0, 5, 0)),
+                (2, aimed(5, 5, 0)),
+                (3, aimed(5, 13, 40)),
+                (4, aimed(2, 13, 40)),
+                (5, aimed(10, 13, 40)),
+                (6, aimed(10, 15, 60)),
+            ]
+        );
+
+        let (step, sub) = trajectory::<Naive>(EXAMPLE).last().unwrap().unwrap();
+        assert_eq!(step, 6);
+        assert_eq!(sub.position_product(), 150);
+    }
+
+    #[test]
+    fn stops_at_bad_line() {
+        let states = trajectory::<Aimed>("down 1\nsideways 2\nforward 3\n").collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            [
+                Ok((0, aimed(0, 0, 0))),
+                Ok((1, aimed(1, 0, 0))),
+                Err(LineError {
+                    line: 2,
+                    error: CommandError::Direction("sideways".to_string())
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv() {
+        let states = trajectory::<Aimed>("forward 5\ndown 5\nforward 8\n").map(Result::unwrap);
+        let mut out = Vec::new();
+        write_csv(&mut out, states).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "step,aim,horizontal,depth\n0,0,0,0\n1,0,5,0\n2,5,5,0\n3,5,13,40\n"
+        );
+    }
+
+    #[test]
+    fn profile() {
+        let states = [(0, aimed(0, 0, 0)), (1, aimed(1, 4, 4))];
+        assert_eq!(
+            depth_profile(&states, 5, 5),
+            "#....\n \
+             #...\n  \
+             #..\n   \
+             #.\n    \
+             #\n"
+        );
+
+        let states = trajectory::<Aimed>(EXAMPLE)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let profile = depth_profile(&states, 80, 10);
+        assert_eq!(profile.lines().count(), 10);
+        assert!(profile.lines().all(|line| line.chars().count() == 80));
+        assert!(profile.starts_with('#'));
+
+        assert_eq!(depth_profile(&states[..1], 3, 2), "#  \n   \n");
+        assert_eq!(depth_profile::<Aimed>(&[], 3, 2), "");
+
+        let states = trajectory::<Aimed<i64>>("down 2\nforward 4000000000\n")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(depth_profile(&states, 3, 3), "#..\n #.\n  #\n");
+    }
+}
@@ -1,43 +1,61 @@
-use std::str::FromStr;
+use day2_part1::script::Script;
+use day2_part1::trajectory::{depth_profile, trajectory, write_csv};
+use day2_part1::{exit_on_error, exit_with, expect_args, run_checked, Aimed};
+use std::io;
 
-fn main() {
-    let input = include_str!("../../input.txt");
-
-    let (_, horiz, depth) = input
-        .lines()
-        .map(|step| step.parse().unwrap())
-        .fold((0, 0, 0), |(aim, horiz, depth), cmd| match cmd {
-            Command::Thrust(dist) => (aim, horiz + dist, depth + (aim * dist)),
-            Command::Pitch(tilt) => (aim + tilt, horiz, depth)
-        });
-
-    println!("{}", horiz * depth);
-
-}
-
-enum Command {
-    Thrust(i32),
-    Pitch(i32),
-}
+/// Rows in the depth profile.
+const PROFILE_HEIGHT: usize = 24;
 
-impl FromStr for Command {
-    type Err = String;
+fn main() -> io::Result<()> {
+    let input = include_str!("../../input.txt");
 
-    fn from_str(input: &str) -> Result<Command, Self::Err> {
-        if let Some((dir, dist)) = input.split_once(' ') {
-            if let Ok(dist) = dist.parse() {
-                match dir {
-                    "forward" => Ok(Command::Thrust(dist)),
-                    "down" => Ok(Command::Pitch(dist)),
-                    "up" => Ok(Command::Pitch(-dist)),
-                    d => Err(format!("Could not parse direction {} in input {}", d, input))
-                }
-            } else {
-                Err(format!("Could not parse distance {} in input {}", dist, input))
-            }
-        } else {
-            Err(format!("Could not divide direction and distance in input {}", input))
+    // `--csv` prints every state, `--profile` draws the depth against horizontal position as
+    // wide as the terminal. `--script <path>` follows a scenario script rather than the
+    // puzzle input. Otherwise, just the answer.
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("--csv") => {
+            expect_args(&args, 1);
+            let states: Vec<_> = exit_on_error(trajectory::<Aimed<i64>>(input).collect());
+            write_csv(io::stdout().lock(), states)?;
+        }
+        Some("--profile") => {
+            expect_args(&args, 1);
+            let states: Vec<_> = exit_on_error(trajectory::<Aimed<i64>>(input).collect());
+            print!(
+                "{}",
+                depth_profile(&states, terminal_width(), PROFILE_HEIGHT)
+            );
+        }
+        Some("--script") => {
+            let path = exit_on_error(args.get(1).ok_or("--script needs a path"));
+            expect_args(&args, 2);
+            let script =
+                exit_on_error(Script::load(path).map_err(|err| format!("{}: {}", path, err)));
+            let sub = exit_on_error(script.run_checked::<Aimed<i64>>());
+            println!("{}", i128::from(sub.horiz) * i128::from(sub.depth));
+        }
+        Some(arg) => exit_with(format_args!("unknown argument {:?}", arg)),
+        None => {
+            let sub = exit_on_error(run_checked::<Aimed<i64>>(input));
+            // two i64s always multiply into an i128
+            println!("{}", i128::from(sub.horiz) * i128::from(sub.depth));
         }
     }
+    Ok(())
 }
 
+/// The width of the terminal stdout is connected to. When it isn't one, like when piped,
+/// fall back to `COLUMNS`, which shells set but don't export, so it has to be passed along
+/// by hand (`COLUMNS=$COLUMNS`). Failing that, assume a classic 80.
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(cols), _)| usize::from(cols))
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|cols| cols.parse().ok())
+        })
+        .filter(|&cols| cols > 0)
+        .unwrap_or(80)
+}
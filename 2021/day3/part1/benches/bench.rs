@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day3_part1::{bitsliced, Report};
+
+const WORD_LEN: usize = 12;
+
+/// The fixed width approach the old part 2 took: each word becomes an array of digits,
+/// and the arrays are summed column by column.
+fn array_zip(input: &str) -> u64 {
+    let (num_words, sums) = input
+        .lines()
+        .map(|word| word.as_bytes())
+        .map(|word| word.try_into().unwrap())
+        .map(|word: [u8; WORD_LEN]| word.map(|b| b - b'0'))
+        .map(|word| word.map(u32::from))
+        .map(|word| (1u32, word))
+        .reduce(|(count, sum), (_, word)| (count + 1, std::array::from_fn(|i| sum[i] + word[i])))
+        .unwrap();
+
+    let half_num_words = num_words / 2;
+
+    let gamma = sums.map(|s| s > half_num_words);
+
+    let gamma = gamma.iter().fold(0, |num, &b| (num << 1) | b as u64);
+    let epsilon = !gamma & 0x0FFF;
+
+    gamma * epsilon
+}
+
+/// Returns `lines` pseudo-random words, in the same format as the puzzle input.
+fn synthetic(lines: usize) -> String {
+    let mut state = 0x2021_0003_u64;
+    (0..lines)
+        .map(|_| {
+            // xorshift
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            format!("{:0width$b}\n", state >> (64 - WORD_LEN), width = WORD_LEN)
+        })
+        .collect()
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let inputs = [
+        ("input", include_str!("../../input.txt").to_string()),
+        ("10k", synthetic(10_000)),
+        ("1M", synthetic(1_000_000)),
+    ];
+
+    let mut group = c.benchmark_group("day3");
+    for (name, input) in &inputs {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            group.bench_with_input(BenchmarkId::new("avx2", name), input, |b, input| {
+                b.iter(|| unsafe { Report::from_ascii_avx2(black_box(input.as_bytes())) })
+            });
+        }
+
+        group.bench_with_input(BenchmarkId::new("portable", name), input, |b, input| {
+            b.iter(|| Report::from_ascii_portable(black_box(input.as_bytes())))
+        });
+
+        group.bench_with_input(BenchmarkId::new("scalar", name), input, |b, input| {
+            b.iter(|| Report::from_ascii(black_box(input.as_bytes())))
+        });
+
+        group.bench_with_input(BenchmarkId::new("bitsliced", name), input, |b, input| {
+            b.iter(|| bitsliced::count_ones(black_box(input.as_bytes()), WORD_LEN))
+        });
+
+        group.bench_with_input(BenchmarkId::new("array_zip", name), input, |b, input| {
+            b.iter(|| array_zip(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+//! The [crate::portable] kernel, with hand picked AVX2 instructions where they help.
+//!
+//! Nothing here should be called without checking for AVX2 first,
+//! see [Report::from_ascii_simd](crate::Report::from_ascii_simd).
+
+use crate::portable::{sum_columns, with_line_len};
+use std::arch::x86_64::*;
+use std::simd::*;
+
+pub use crate::portable::{ones, HW_LANES};
+
+/// The most words that can be summed before the signed compare in [gamma] breaks down.
+pub const MAX_WORDS: usize = i16::MAX as usize;
+
+/// Returns the sum of the bytes in each of the first [HW_LANES] columns, wrapping on overflow.
+/// Returns `None` if the words are too wide for the kernel.
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn column_sums(input: &[u8], width: usize) -> Option<u16x16> {
+    with_line_len!(width, column_sums_for(input))
+}
+
+/// Returns the sum of the bytes in each of the first [HW_LANES] columns, wrapping on overflow.
+/// See [crate::portable::column_sums_for].
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub unsafe fn column_sums_for<const LINE_LEN: usize>(input: &[u8]) -> u16x16 {
+    sum_columns::<LINE_LEN>(input, |word| widen(word))
+}
+
+/// Zero extends each byte to 16 bits.
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn widen(word: u8x16) -> u16x16 {
+    u16x16::from(_mm256_cvtepu8_epi16(word.into()))
+}
+
+/// Returns the gamma rate for the first `width` columns of `ones`.
+///
+/// # Safety
+/// The CPU must support AVX2, and `num_words` must be at most [MAX_WORDS].
+#[inline]
+#[target_feature(enable = "avx2")]
+pub unsafe fn gamma(ones: u16x16, num_words: usize, width: usize) -> u64 {
+    let midpoint = u16x16::splat(num_words as u16 / 2);
+    let gamma = _mm256_cmpgt_epi16(ones.into(), midpoint.into());
+    let gamma_lower = _mm256_extracti128_si256::<0>(gamma);
+    let gamma_upper = _mm256_extracti128_si256::<1>(gamma);
+    let gamma = _mm_packs_epi16(gamma_lower, gamma_upper);
+    let gamma = u8x16::from(gamma).reverse();
+    (_mm_movemask_epi8(gamma.into()) as u32 >> (HW_LANES - width)) as u64
+}
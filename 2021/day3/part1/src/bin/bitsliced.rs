@@ -0,0 +1,25 @@
+//! Like the main solution, but counts with the bit-sliced kernel,
+//! streaming the report from stdin one line at a time.
+
+use day3_part1::bitsliced::BitSliced;
+use day3_part1::{detect_width, ReportError};
+use std::error::Error;
+use std::io::{self, BufRead};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut lines = io::stdin().lock().split(b'\n');
+
+    let first = lines.next().transpose()?.ok_or(ReportError::Empty)?;
+    let mut counter = BitSliced::new(detect_width(&first)?);
+    counter.push_ascii(1, &first)?;
+
+    for (line, word) in lines.enumerate() {
+        counter.push_ascii(line + 2, &word?)?;
+    }
+
+    let report = counter.finish();
+
+    println!("{}", report.power_consumption());
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Counts columns by packing each word into an integer and adding them up bit-sliced.
+//!
+//! Rather than keeping a counter per column, the counters are stored "vertically": bit `j` of
+//! the `i`th plane is bit `i` of column `j`'s counter, so one integer operation works on every
+//! column at once. Words are first combined sixteen at a time with a Harley-Seal tree of carry
+//! save adders, and only the resulting sixteens are rippled into the vertical counter.
+//!
+//! Words are pushed one at a time, so there is no need to hold the whole input in memory.
+
+use crate::{gamma_from_ones, scalar, Report, ReportError, MAX_WIDTH};
+
+/// Enough planes to count 2^64 words.
+const PLANES: usize = u64::BITS as usize - 4;
+
+/// Bit-sliced counters for the ones in each column.
+#[derive(Clone, Debug)]
+pub struct BitSliced {
+    width: usize,
+    num_words: usize,
+    ones: u64,
+    twos: u64,
+    fours: u64,
+    eights: u64,
+    sixteens: [u64; PLANES],
+    block: [u64; 16],
+    block_len: usize,
+}
+
+impl BitSliced {
+    /// Returns counters for words `width` bits wide, which must be at most [MAX_WIDTH].
+    pub fn new(width: usize) -> Self {
+        assert!((1..=MAX_WIDTH).contains(&width));
+        BitSliced {
+            width,
+            num_words: 0,
+            ones: 0,
+            twos: 0,
+            fours: 0,
+            eights: 0,
+            sixteens: [0; PLANES],
+            block: [0; 16],
+            block_len: 0,
+        }
+    }
+
+    /// Counts a packed word, whose first column is its most significant bit.
+    #[inline]
+    pub fn push(&mut self, word: u64) {
+        self.block[self.block_len] = word;
+        self.block_len += 1;
+        self.num_words += 1;
+
+        if self.block_len == self.block.len() {
+            self.add_block();
+            self.block_len = 0;
+        }
+    }
+
+    /// Checks and counts a word of ASCII binary digits.
+    /// `line` is only used for the error, and counts from 1.
+    #[inline]
+    pub fn push_ascii(&mut self, line: usize, word: &[u8]) -> Result<(), ReportError> {
+        if word.len() != self.width {
+            return Err(ReportError::BadLength {
+                line,
+                len: word.len(),
+            });
+        }
+        let word = pack(word).map_err(|column| ReportError::BadDigit { line, column })?;
+        self.push(word);
+        Ok(())
+    }
+
+    /// Adds up every plane into a [Report].
+    pub fn finish(self) -> Report {
+        let mut ones = [0; MAX_WIDTH];
+
+        for (column, ones) in ones[..self.width].iter_mut().enumerate() {
+            let bit = self.width - 1 - column;
+            let at = |plane: u64| ((plane >> bit) & 1) as usize;
+
+            let sixteens = self
+                .sixteens
+                .iter()
+                .enumerate()
+                .map(|(i, &plane)| at(plane) << i)
+                .sum::<usize>();
+            let unblocked = self.block[..self.block_len]
+                .iter()
+                .map(|&word| at(word))
+                .sum::<usize>();
+
+            *ones = 16 * sixteens
+                + 8 * at(self.eights)
+                + 4 * at(self.fours)
+                + 2 * at(self.twos)
+                + at(self.ones)
+                + unblocked;
+        }
+
+        let ones = &ones[..self.width];
+        let gamma = gamma_from_ones(ones, self.num_words);
+        Report::new(self.width, self.num_words, ones, gamma)
+    }
+
+    /// Folds a full block of sixteen words into the planes.
+    #[inline]
+    fn add_block(&mut self) {
+        let d = &self.block;
+
+        let (twos_a, ones) = csa(self.ones, d[0], d[1]);
+        let (twos_b, ones) = csa(ones, d[2], d[3]);
+        let (fours_a, twos) = csa(self.twos, twos_a, twos_b);
+        let (twos_a, ones) = csa(ones, d[4], d[5]);
+        let (twos_b, ones) = csa(ones, d[6], d[7]);
+        let (fours_b, twos) = csa(twos, twos_a, twos_b);
+        let (eights_a, fours) = csa(self.fours, fours_a, fours_b);
+
+        let (twos_a, ones) = csa(ones, d[8], d[9]);
+        let (twos_b, ones) = csa(ones, d[10], d[11]);
+        let (fours_a, twos) = csa(twos, twos_a, twos_b);
+        let (twos_a, ones) = csa(ones, d[12], d[13]);
+        let (twos_b, ones) = csa(ones, d[14], d[15]);
+        let (fours_b, twos) = csa(twos, twos_a, twos_b);
+        let (eights_b, fours) = csa(fours, fours_a, fours_b);
+
+        let (sixteens, eights) = csa(self.eights, eights_a, eights_b);
+
+        self.ones = ones;
+        self.twos = twos;
+        self.fours = fours;
+        self.eights = eights;
+
+        // ripple carry, which rarely makes it past the first couple of planes
+        let mut carry = sixteens;
+        for plane in self.sixteens.iter_mut() {
+            if carry == 0 {
+                break;
+            }
+            let next = *plane & carry;
+            *plane ^= carry;
+            carry = next;
+        }
+    }
+}
+
+/// A carry save adder. Adds three bits in every position, returning the carries and sums.
+#[inline(always)]
+fn csa(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let u = a ^ b;
+    ((a & b) | (u & c), u ^ c)
+}
+
+/// Packs a word of ASCII binary digits into an integer, first digit most significant.
+/// On failure, returns the column (counting from 1) of the first bad digit.
+#[inline]
+pub fn pack(word: &[u8]) -> Result<u64, usize> {
+    word.iter()
+        .enumerate()
+        .try_fold(0, |n, (column, &b)| match b {
+            b'0' => Ok(n << 1),
+            b'1' => Ok((n << 1) | 1),
+            _ => Err(column + 1),
+        })
+}
+
+/// Counts the ones in each column of `input`, whose words are `width` bits wide.
+pub fn count_ones(input: &[u8], width: usize) -> Result<Report, ReportError> {
+    let mut counter = BitSliced::new(width);
+    for (line, word) in scalar::words(input).enumerate() {
+        counter.push_ascii(line + 1, word)?;
+    }
+    Ok(counter.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{detect_width, mask};
+
+    fn matches_scalar(input: &[u8]) {
+        let width = detect_width(input).unwrap();
+        assert_eq!(count_ones(input, width), scalar::count_ones(input, width));
+    }
+
+    #[test]
+    fn small() {
+        matches_scalar(b"1\n");
+        matches_scalar(b"00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n");
+        matches_scalar(include_bytes!("../../input.txt"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn many_wide_words() {
+        let mut state = 1u64;
+        let input = (0..100_003)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                format!("{:064b}\n", state)
+            })
+            .collect::<String>();
+        matches_scalar(input.as_bytes());
+
+        let input = (0..4099)
+            .map(|i| format!("{:07b}\n", i & mask(7)))
+            .collect::<String>();
+        matches_scalar(input.as_bytes());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            count_ones(b"101\n10\n", 3),
+            Err(ReportError::BadLength { line: 2, len: 2 })
+        );
+        assert_eq!(
+            count_ones(b"101\n1x1\n", 3),
+            Err(ReportError::BadDigit { line: 2, column: 2 })
+        );
+    }
+}
@@ -0,0 +1,396 @@
+#![feature(portable_simd)]
+
+//! Column statistics for the binary diagnostic report in day 3 of Advent of Code 2021.
+//!
+//! The word width is detected from the first line, so anything from 1 to [MAX_WIDTH] bits works.
+
+use std::fmt;
+
+#[cfg(target_arch = "x86_64")]
+pub mod avx2;
+pub mod bitsliced;
+pub mod portable;
+pub mod scalar;
+
+use std::simd::u16x16;
+
+/// The widest word a [Report] can hold.
+pub const MAX_WIDTH: usize = 64;
+
+/// Per column counts of a diagnostic report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    width: usize,
+    num_words: usize,
+    ones: [usize; MAX_WIDTH],
+    gamma: u64,
+}
+
+impl Report {
+    /// Counts the ones in each column of a newline separated list of binary words.
+    pub fn from_ascii(input: &[u8]) -> Result<Self, ReportError> {
+        let width = detect_width(input)?;
+        scalar::count_ones(input, width)
+    }
+
+    /// Like [Report::from_ascii], but uses a SIMD kernel when the width allows it.
+    ///
+    /// The AVX2 kernel is used if the CPU supports it, and the portable one otherwise.
+    /// Neither checks its input, so anything malformed is counted by [scalar] instead, and
+    /// the result is always the same as [Report::from_ascii]'s.
+    pub fn from_ascii_simd(input: &[u8]) -> Result<Self, ReportError> {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { Self::from_ascii_avx2(input) };
+        }
+
+        Self::from_ascii_portable(input)
+    }
+
+    /// Like [Report::from_ascii_simd], but always uses the portable kernel.
+    pub fn from_ascii_portable(input: &[u8]) -> Result<Self, ReportError> {
+        Self::from_kernel(
+            input,
+            portable::MAX_WORDS,
+            portable::column_sums,
+            portable::gamma,
+        )
+    }
+
+    /// Like [Report::from_ascii_simd], but always uses the AVX2 kernel.
+    ///
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn from_ascii_avx2(input: &[u8]) -> Result<Self, ReportError> {
+        Self::from_kernel(
+            input,
+            avx2::MAX_WORDS,
+            |input, width| unsafe { avx2::column_sums(input, width) },
+            |ones, num_words, width| unsafe { avx2::gamma(ones, num_words, width) },
+        )
+    }
+
+    /// Counts with a SIMD kernel, falling back to [scalar] for anything it can't take.
+    ///
+    /// The kernels count in 16 bit lanes, so inputs of more than `max_words` words are split
+    /// into batches, whose counts are added up here.
+    #[inline(always)]
+    fn from_kernel(
+        input: &[u8],
+        max_words: usize,
+        column_sums: impl Fn(&[u8], usize) -> Option<u16x16>,
+        gamma: impl Fn(u16x16, usize, usize) -> u64,
+    ) -> Result<Self, ReportError> {
+        let width = detect_width(input)?;
+        let line_len = width + 1;
+        let Some(num_words) = simd_num_words(input, width) else {
+            return scalar::count_ones(input, width);
+        };
+
+        let mut ones = [0; portable::HW_LANES];
+        for batch in input.chunks(max_words * line_len) {
+            let Some(sums) = column_sums(batch, width) else {
+                return scalar::count_ones(input, width);
+            };
+            let batch_ones = portable::ones(sums, (batch.len() + 1) / line_len);
+
+            if num_words <= max_words {
+                let gamma = gamma(batch_ones, num_words, width);
+                let ones = batch_ones.to_array().map(usize::from);
+                return Ok(Report::new(width, num_words, &ones[..width], gamma));
+            }
+
+            for (ones, batch_ones) in ones.iter_mut().zip(batch_ones.to_array()) {
+                *ones += batch_ones as usize;
+            }
+        }
+
+        let gamma = gamma_from_ones(&ones[..width], num_words);
+        Ok(Report::new(width, num_words, &ones[..width], gamma))
+    }
+
+    /// Builds a report from the ones in each column, most significant first.
+    pub(crate) fn new(width: usize, num_words: usize, ones: &[usize], gamma: u64) -> Self {
+        let mut report = Report {
+            width,
+            num_words,
+            ones: [0; MAX_WIDTH],
+            gamma,
+        };
+        report.ones[..width].copy_from_slice(ones);
+        report
+    }
+
+    /// The number of bits in each word.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of words in the report.
+    #[inline]
+    pub fn num_words(&self) -> usize {
+        self.num_words
+    }
+
+    /// The number of ones in each column, most significant first.
+    #[inline]
+    pub fn ones(&self) -> &[usize] {
+        &self.ones[..self.width]
+    }
+
+    /// Each bit is set if ones are the most common bit in that column.
+    #[inline]
+    pub fn gamma(&self) -> u64 {
+        self.gamma
+    }
+
+    /// Each bit is set if ones are the least common bit in that column.
+    #[inline]
+    pub fn epsilon(&self) -> u64 {
+        !self.gamma & mask(self.width)
+    }
+
+    /// The puzzle answer. A [u128], since two 64 bit rates can't be multiplied in a [u64].
+    #[inline]
+    pub fn power_consumption(&self) -> u128 {
+        self.gamma() as u128 * self.epsilon() as u128
+    }
+}
+
+/// Returns the gamma rate for the given column counts.
+/// Ties are counted as zeroes.
+pub fn gamma_from_ones(ones: &[usize], num_words: usize) -> u64 {
+    ones.iter().fold(0, |gamma, &ones| {
+        (gamma << 1) | (2 * ones > num_words) as u64
+    })
+}
+
+/// Returns the number of words in `input`, if a SIMD kernel can take it as is.
+///
+/// Every line must be `width` binary digits plus a newline, though the last newline may be
+/// missing. The kernels count whatever bytes they're given, so anything else is left to
+/// [scalar], which reports what's wrong with it.
+fn simd_num_words(input: &[u8], width: usize) -> Option<usize> {
+    let line_len = width + 1;
+    let well_formed = input.chunks(line_len).all(|line| {
+        let (word, newline) = line.split_at(line.len().min(width));
+        word.len() == width
+            && word.iter().all(|&b| b == b'0' || b == b'1')
+            && matches!(newline, b"" | b"\n")
+    });
+
+    well_formed.then_some((input.len() + 1) / line_len)
+}
+
+/// Returns the number of bits per word, based on the first line of `input`.
+pub fn detect_width(input: &[u8]) -> Result<usize, ReportError> {
+    let width = input
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(input.len());
+
+    match width {
+        0 => Err(ReportError::Empty),
+        w if w > MAX_WIDTH => Err(ReportError::TooWide(w)),
+        w => Ok(w),
+    }
+}
+
+/// A mask of the low `width` bits.
+#[inline]
+pub fn mask(width: usize) -> u64 {
+    u64::MAX >> (u64::BITS as usize - width)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReportError {
+    /// The first line is empty.
+    Empty,
+    /// The first line has more than [MAX_WIDTH] digits.
+    TooWide(usize),
+    /// A line (counting from 1) has a different width than the first.
+    BadLength { line: usize, len: usize },
+    /// A line (counting from 1) has something other than a `0` or `1` in it.
+    BadDigit { line: usize, column: usize },
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::Empty => write!(f, "Report is empty"),
+            ReportError::TooWide(w) => {
+                write!(
+                    f,
+                    "Words are {} bits wide, at most {} are supported",
+                    w, MAX_WIDTH
+                )
+            }
+            ReportError::BadLength { line, len } => {
+                write!(
+                    f,
+                    "Line {} is {} bits wide, unlike the first line",
+                    line, len
+                )
+            }
+            ReportError::BadDigit { line, column } => {
+                write!(
+                    f,
+                    "Line {} has a non-binary digit in column {}",
+                    line, column
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "00100\n11110\n10110\n10111\n10101\n01111\n\
+                           00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    #[test]
+    fn example() {
+        let report = Report::from_ascii(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(report.width(), 5);
+        assert_eq!(report.num_words(), 12);
+        assert_eq!(report.ones(), &[7, 5, 8, 7, 5]);
+        assert_eq!(report.gamma(), 22);
+        assert_eq!(report.epsilon(), 9);
+        assert_eq!(report.power_consumption(), 198);
+    }
+
+    #[test]
+    fn no_trailing_newline() {
+        let report = Report::from_ascii(EXAMPLE.trim_end().as_bytes()).unwrap();
+        assert_eq!(report.power_consumption(), 198);
+    }
+
+    #[test]
+    fn widest() {
+        let input = format!("{:064b}\n{:064b}\n{:064b}\n", u64::MAX, 1u64 << 63, 1);
+        let report = Report::from_ascii(input.as_bytes()).unwrap();
+        assert_eq!(report.gamma(), 1 << 63 | 1);
+        assert_eq!(report.epsilon(), !(1 << 63 | 1));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Report::from_ascii(b"\n101\n"), Err(ReportError::Empty));
+        assert_eq!(
+            Report::from_ascii(&[b'0'; 65]),
+            Err(ReportError::TooWide(65))
+        );
+        assert_eq!(
+            Report::from_ascii(b"101\n10\n"),
+            Err(ReportError::BadLength { line: 2, len: 2 })
+        );
+        assert_eq!(
+            Report::from_ascii(b"101\n121\n"),
+            Err(ReportError::BadDigit { line: 2, column: 2 })
+        );
+    }
+
+    /// Checks a SIMD constructor against the scalar one, on inputs with awkward lengths,
+    /// placements and widths.
+    fn matches_scalar(from_ascii: impl Fn(&[u8]) -> Result<Report, ReportError>) {
+        let input = include_bytes!("../../input.txt");
+        let example = EXAMPLE.repeat(10);
+        // every width the kernels handle, with lines of a scrambled count pattern
+        let widths = (1..=16).map(|width| {
+            (0u64..37)
+                .map(|i| format!("{:0width$b}\n", (i * 0x9E37_79B9) & mask(width)))
+                .collect::<String>()
+        });
+        let inputs = [1, 15, 16, 17, 33, 1000]
+            .map(|lines| input[..lines * 13].to_vec())
+            .into_iter()
+            .chain([example.into_bytes()])
+            .chain(widths.map(String::into_bytes));
+
+        for text in inputs {
+            let expected = Report::from_ascii(&text);
+            assert!(expected.is_ok());
+
+            // an exactly sized heap allocation, so any read past the end is out of bounds
+            let heap = text.to_vec();
+            assert_eq!(from_ascii(&heap), expected);
+
+            // without the last newline
+            let heap = text[..text.len() - 1].to_vec();
+            assert_eq!(from_ascii(&heap), expected);
+
+            // starting at an odd address
+            let mut heap = vec![0; text.len() + 1];
+            heap[1..].copy_from_slice(&text);
+            assert_eq!(from_ascii(&heap[1..]), expected);
+        }
+
+        assert_eq!(
+            from_ascii(b"101\n10\n111\n"),
+            Err(ReportError::BadLength { line: 2, len: 2 })
+        );
+        // a newline where a digit should be, which still puts one every `width + 1` bytes
+        for text in [&b"0\n\n"[..], b"10\n1\n\n", b"10\n21\n"] {
+            assert_eq!(from_ascii(text), Report::from_ascii(text));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn batches() {
+        // too many words for the 16 bit lanes to count in one go
+        let input = (0u64..100_000)
+            .map(|i| format!("{:05b}\n", ((i * 0x9E37_79B9) >> 7) & 0b11111))
+            .collect::<String>();
+        let expected = Report::from_ascii(input.as_bytes());
+        assert_eq!(Report::from_ascii_portable(input.as_bytes()), expected);
+        assert_eq!(Report::from_ascii_simd(input.as_bytes()), expected);
+    }
+
+    /// Inputs every constructor has to reject the way [Report::from_ascii] does.
+    const MALFORMED: [&[u8]; 8] = [
+        b"",
+        b"\n101\n",
+        b"101\n10\n",
+        b"101\n121\n",
+        b"101\n1011\n",
+        b"0\n\n",
+        b"10\n1\n\n",
+        b"10\n21\n",
+    ];
+
+    #[test]
+    fn portable_matches_scalar() {
+        matches_scalar(Report::from_ascii_portable);
+        for text in MALFORMED {
+            let expected = Report::from_ascii(text);
+            assert!(expected.is_err());
+            assert_eq!(Report::from_ascii_portable(text), expected);
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        matches_scalar(Report::from_ascii_simd);
+        for text in MALFORMED {
+            let expected = Report::from_ascii(text);
+            assert!(expected.is_err());
+            assert_eq!(Report::from_ascii_simd(text), expected);
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn avx2_matches_scalar() {
+        if is_x86_feature_detected!("avx2") {
+            matches_scalar(|input| unsafe { Report::from_ascii_avx2(input) });
+        }
+    }
+}
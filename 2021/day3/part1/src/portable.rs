@@ -0,0 +1,119 @@
+//! Sums columns sixteen words at a time with nothing but [std::simd], so it runs anywhere.
+//!
+//! Every word is loaded as a full vector of [HW_LANES] bytes, wherever it sits in the input.
+//! Lanes past the end of the word pick up the newline and the start of the next word, which
+//! are summed along with everything else and ignored at the end.
+//! Near the end of the input, masked loads stand in for those lanes, so nothing outside of
+//! the input is ever read and it needs no particular alignment.
+
+use std::simd::cmp::SimdPartialOrd;
+use std::simd::num::SimdUint;
+use std::simd::*;
+
+pub const HW_LANES: usize = 16;
+
+/// The most words that can be summed before a column's count of ones could overflow.
+pub const MAX_WORDS: usize = u16::MAX as usize;
+
+/// Picks the kernel for words `$width` bits wide, or evaluates to `None` if they are too wide.
+macro_rules! with_line_len {
+    ($width:expr, $kernel:ident($($arg:expr),*)) => {
+        match $width + 1 {
+            2 => Some($kernel::<2>($($arg),*)),
+            3 => Some($kernel::<3>($($arg),*)),
+            4 => Some($kernel::<4>($($arg),*)),
+            5 => Some($kernel::<5>($($arg),*)),
+            6 => Some($kernel::<6>($($arg),*)),
+            7 => Some($kernel::<7>($($arg),*)),
+            8 => Some($kernel::<8>($($arg),*)),
+            9 => Some($kernel::<9>($($arg),*)),
+            10 => Some($kernel::<10>($($arg),*)),
+            11 => Some($kernel::<11>($($arg),*)),
+            12 => Some($kernel::<12>($($arg),*)),
+            13 => Some($kernel::<13>($($arg),*)),
+            14 => Some($kernel::<14>($($arg),*)),
+            15 => Some($kernel::<15>($($arg),*)),
+            16 => Some($kernel::<16>($($arg),*)),
+            _ => None,
+        }
+    };
+}
+pub(crate) use with_line_len;
+
+/// Returns the sum of the bytes in each of the first [HW_LANES] columns, wrapping on overflow.
+/// Returns `None` if the words are too wide for the kernel.
+#[inline]
+pub fn column_sums(input: &[u8], width: usize) -> Option<u16x16> {
+    with_line_len!(width, column_sums_for(input))
+}
+
+/// Returns the sum of the bytes in each of the first [HW_LANES] columns, wrapping on overflow.
+///
+/// `input` must consist of whole lines of `LINE_LEN` bytes, though the last newline may be
+/// missing. Anything else gives meaningless sums, but never reads outside of `input`.
+#[inline]
+pub fn column_sums_for<const LINE_LEN: usize>(input: &[u8]) -> u16x16 {
+    sum_columns::<LINE_LEN>(input, widen)
+}
+
+/// The kernel itself, shared with [crate::avx2], which brings its own `widen`.
+#[inline(always)]
+pub(crate) fn sum_columns<const LINE_LEN: usize>(
+    input: &[u8],
+    widen: impl Fn(u8x16) -> u16x16,
+) -> u16x16 {
+    let blocks = input.chunks_exact(HW_LANES * LINE_LEN);
+    let tail = blocks.remainder();
+
+    // each block of HW_LANES words is summed as LINE_LEN full vectors,
+    // so lanes line up with byte offsets into the block rather than columns
+    let block_sums = blocks
+        .map(|block| std::array::from_fn(|i| widen(u8x16::from_slice(&block[i * HW_LANES..]))))
+        .fold(
+            [u16x16::splat(0); LINE_LEN],
+            |acc, words: [u16x16; LINE_LEN]| std::array::from_fn(|i| acc[i] + words[i]),
+        );
+
+    // TODO: maybe try to keep these inside registers and shift
+    // instead of storing/loading
+    let block_sums = block_sums.map(u16x16::to_array);
+    let block_sums = block_sums.as_flattened();
+
+    // then each word of the block is picked back out and summed.
+    // the last word's vector would run off the end of the block, but a masked load
+    // fills the missing lanes with zeroes, which only end up in columns past the word
+    let block_sums = (0..HW_LANES)
+        .map(|word| u16x16::load_or_default(&block_sums[word * LINE_LEN..]))
+        .sum::<u16x16>();
+
+    // the words which don't fill a block are loaded one at a time, with the same masked
+    // load keeping the last one from reading past the end of the input
+    let tail_sums = (0..tail.len())
+        .step_by(LINE_LEN)
+        .map(|word| widen(u8x16::load_or_default(&tail[word..])))
+        .sum::<u16x16>();
+
+    block_sums + tail_sums
+}
+
+/// Zero extends each byte to 16 bits.
+#[inline]
+pub fn widen(word: u8x16) -> u16x16 {
+    word.cast()
+}
+
+/// Turns column sums of ASCII digits into counts of ones.
+#[inline]
+pub fn ones(sums: u16x16, num_words: usize) -> u16x16 {
+    sums - u16x16::splat((b'0' as u16).wrapping_mul(num_words as u16))
+}
+
+/// Returns the gamma rate for the first `width` columns of `ones`.
+/// `num_words` must be at most [MAX_WORDS].
+#[inline]
+pub fn gamma(ones: u16x16, num_words: usize, width: usize) -> u64 {
+    let midpoint = u16x16::splat((num_words / 2) as u16);
+    // the first column is the most significant bit
+    let gamma = (ones.simd_gt(midpoint).to_bitmask() as u16).reverse_bits();
+    (gamma >> (HW_LANES - width)) as u64
+}
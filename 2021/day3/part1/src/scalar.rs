@@ -0,0 +1,45 @@
+//! Counts one word at a time. Works for any width, and checks its input as it goes.
+
+use crate::{gamma_from_ones, Report, ReportError, MAX_WIDTH};
+
+/// Counts the ones in each column of `input`, whose words are `width` bits wide.
+pub fn count_ones(input: &[u8], width: usize) -> Result<Report, ReportError> {
+    let mut ones = [0; MAX_WIDTH];
+    let mut num_words = 0;
+
+    for (line, word) in words(input).enumerate() {
+        if word.len() != width {
+            return Err(ReportError::BadLength {
+                line: line + 1,
+                len: word.len(),
+            });
+        }
+
+        for (column, (sum, &b)) in ones.iter_mut().zip(word).enumerate() {
+            match b {
+                b'0' => (),
+                b'1' => *sum += 1,
+                _ => {
+                    return Err(ReportError::BadDigit {
+                        line: line + 1,
+                        column: column + 1,
+                    })
+                }
+            }
+        }
+
+        num_words += 1;
+    }
+
+    let gamma = gamma_from_ones(&ones[..width], num_words);
+    Ok(Report::new(width, num_words, &ones[..width], gamma))
+}
+
+/// Splits `input` into lines, ignoring the final newline.
+#[inline]
+pub fn words(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    input
+        .strip_suffix(b"\n")
+        .unwrap_or(input)
+        .split(|&b| b == b'\n')
+}
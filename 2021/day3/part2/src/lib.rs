@@ -0,0 +1,134 @@
+//! Life support ratings for day 3 of Advent of Code 2021.
+//!
+//! Both ratings keep filtering the words down by the most (or least) common bit in each column.
+//! Rather than filtering into new lists, the words are partitioned in place one column at a
+//! time, like a most significant digit radix sort. The words still in the running are then
+//! always a contiguous run of the array, and finding both ratings takes O(n·w).
+
+use day3_part1::{scalar, Report, ReportError};
+
+/// The oxygen generator and CO2 scrubber ratings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifeSupport {
+    pub oxygen: u64,
+    pub co2: u64,
+}
+
+impl LifeSupport {
+    /// Finds both ratings in a newline separated list of binary words.
+    pub fn from_ascii(input: &[u8]) -> Result<Self, ReportError> {
+        // checks every word, and counts the ones in each column
+        let report = Report::from_ascii(input)?;
+        let width = report.width();
+
+        let mut words = scalar::words(input)
+            .map(|word| word.iter().fold(0, |n, &b| (n << 1) | (b - b'0') as u64))
+            .collect::<Vec<_>>();
+
+        // ties go to ones for oxygen, and to zeroes for CO2
+        let most_common = |zeros: usize, ones: usize| ones >= zeros;
+        let least_common = |zeros: usize, ones: usize| ones < zeros;
+
+        // the first column has already been counted, so that's one less pass to make
+        let num_ones = report.ones()[0];
+        let first = |keep_ones: fn(usize, usize) -> bool| {
+            keep_ones(report.num_words() - num_ones, num_ones)
+        };
+        let oxygen = rating(&mut words, width, first(most_common), most_common);
+        let co2 = rating(&mut words, width, first(least_common), least_common);
+
+        Ok(LifeSupport { oxygen, co2 })
+    }
+
+    /// The puzzle answer.
+    #[inline]
+    pub fn rating(&self) -> u128 {
+        self.oxygen as u128 * self.co2 as u128
+    }
+}
+
+/// Filters `words` down to a single word, column by column.
+///
+/// `keep_ones` is given the number of zeroes and ones in a column, and decides which stay.
+/// `first_keep_ones` is its answer for the first column, which is already known.
+/// If every word left has the same bit, they all stay.
+fn rating(
+    mut words: &mut [u64],
+    width: usize,
+    first_keep_ones: bool,
+    keep_ones: fn(usize, usize) -> bool,
+) -> u64 {
+    for bit in (0..width).rev() {
+        if words.len() <= 1 {
+            break;
+        }
+
+        let split = partition(words, bit);
+        let (zeros, ones) = words.split_at_mut(split);
+
+        let keep_ones = match bit + 1 == width {
+            true => first_keep_ones,
+            false => keep_ones(zeros.len(), ones.len()),
+        };
+        words = match (keep_ones, zeros.is_empty(), ones.is_empty()) {
+            (_, true, _) | (true, _, false) => ones,
+            _ => zeros,
+        };
+    }
+
+    // anything left over is a duplicate
+    words[0]
+}
+
+/// Moves the words with `bit` unset in front of those with it set.
+/// Returns the index of the first word with `bit` set.
+fn partition(words: &mut [u64], bit: usize) -> usize {
+    let mut split = 0;
+    for i in 0..words.len() {
+        if (words[i] >> bit) & 1 == 0 {
+            words.swap(split, i);
+            split += 1;
+        }
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "00100\n11110\n10110\n10111\n10101\n01111\n\
+                           00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    #[test]
+    fn example() {
+        let life_support = LifeSupport::from_ascii(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(
+            life_support,
+            LifeSupport {
+                oxygen: 23,
+                co2: 10
+            }
+        );
+        assert_eq!(life_support.rating(), 230);
+    }
+
+    #[test]
+    fn single_word() {
+        let life_support = LifeSupport::from_ascii(b"0110\n").unwrap();
+        assert_eq!(life_support, LifeSupport { oxygen: 6, co2: 6 });
+    }
+
+    #[test]
+    fn shared_bits() {
+        // every word starts with 1, so CO2 has nothing to throw away in that column
+        let life_support = LifeSupport::from_ascii(b"100\n110\n111\n").unwrap();
+        assert_eq!(life_support, LifeSupport { oxygen: 7, co2: 4 });
+    }
+
+    #[test]
+    fn duplicates() {
+        let life_support = LifeSupport::from_ascii(b"01\n01\n01\n").unwrap();
+        assert_eq!(life_support, LifeSupport { oxygen: 1, co2: 1 });
+    }
+}
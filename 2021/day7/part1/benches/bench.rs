@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day7_part1::{median, parse};
+
+/// Returns `crabs` pseudo-random positions, spread over every possible one.
+fn synthetic(crabs: usize) -> Vec<u16> {
+    let mut state = 0x2021_0007_u64;
+    (0..crabs)
+        .map(|_| {
+            // xorshift
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 48) as u16
+        })
+        .collect()
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let inputs = [
+        ("input", parse(include_str!("../../input.txt")).unwrap()),
+        ("16k", synthetic(16_384)),
+        ("10M", synthetic(10_000_000)),
+    ];
+
+    let mut group = c.benchmark_group("median");
+    for (name, crabs) in &inputs {
+        group.throughput(Throughput::Elements(crabs.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("sorting", name), crabs, |b, crabs| {
+            b.iter(|| median::by_sorting(black_box(crabs)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("selection", name), crabs, |b, crabs| {
+            b.iter(|| median::by_selection(black_box(crabs)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("counting", name), crabs, |b, crabs| {
+            b.iter(|| median::by_counting(black_box(crabs)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,287 @@
+//! Lining up crab submarines for day 7 of Advent of Code 2021.
+//!
+//! Each [CostModel] says how much fuel a crab burns to move a given distance, and finds the
+//! position that costs the fewest fuel to align every crab on.
+
+use std::fmt;
+use std::num::IntErrorKind;
+
+pub mod median;
+
+/// Parses a comma separated list of crab positions.
+pub fn parse(input: &str) -> Result<Vec<u16>, ParseError> {
+    input
+        .trim()
+        .split(',')
+        .enumerate()
+        .map(|(i, pos)| {
+            pos.parse().map_err(|err: std::num::ParseIntError| {
+                let crab = i + 1;
+                let pos = pos.to_string();
+                match err.kind() {
+                    IntErrorKind::PosOverflow => ParseError::OutOfRange { crab, pos },
+                    _ => ParseError::NotAPosition { crab, pos },
+                }
+            })
+        })
+        .collect()
+}
+
+/// Why [parse] failed, with the crab (counting from 1) it failed at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Past the highest position, [u16::MAX].
+    OutOfRange {
+        crab: usize,
+        pos: String,
+    },
+    NotAPosition {
+        crab: usize,
+        pos: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::OutOfRange { crab, pos } => write!(
+                f,
+                "crab {} is at {}, past the highest position of {}",
+                crab,
+                pos,
+                u16::MAX
+            ),
+            ParseError::NotAPosition { crab, pos } => {
+                write!(f, "crab {} is at {:?}, which is not a position", crab, pos)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A position to align on, and the fuel it takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: u16,
+    pub fuel: u64,
+}
+
+/// How much fuel a crab burns to move.
+pub trait CostModel {
+    /// The fuel to move one crab `distance` steps.
+    fn cost(&self, distance: u64) -> u64;
+
+    /// The fuel to move every crab to `position`.
+    fn total(&self, crabs: &[u16], position: u16) -> u64 {
+        crabs
+            .iter()
+            .map(|&crab| self.cost(u64::from(crab.abs_diff(position))))
+            .sum()
+    }
+
+    /// The cheapest position to align on, or `None` if there are no crabs.
+    ///
+    /// By default, this ternary searches between the outermost crabs, which finds the
+    /// optimum as long as [CostModel::cost] is convex and never decreases with distance.
+    /// Each crab's cost is then convex in the position, and so is their total. A cost which
+    /// dips first, like `(d - 5)²`, makes a crab's cost dip on both sides of it, and the
+    /// search can settle on the wrong one.
+    fn align(&self, crabs: &[u16]) -> Option<Alignment> {
+        ternary_search(crabs, |position| self.total(crabs, position))
+    }
+}
+
+/// Part 1: every step costs 1 fuel.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Linear;
+
+impl CostModel for Linear {
+    #[inline]
+    fn cost(&self, distance: u64) -> u64 {
+        distance
+    }
+
+    /// The median, since moving away from it brings more crabs further than it brings closer.
+    fn align(&self, crabs: &[u16]) -> Option<Alignment> {
+        let position = median::median(crabs)?;
+        Some(Alignment {
+            position,
+            fuel: self.total(crabs, position),
+        })
+    }
+}
+
+/// Part 2: each step costs 1 more fuel than the last.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Triangular;
+
+impl CostModel for Triangular {
+    #[inline]
+    fn cost(&self, distance: u64) -> u64 {
+        distance * (distance + 1) / 2
+    }
+
+    /// Near the mean. The cost is mostly quadratic, which the mean minimises, and the linear
+    /// half of `n(n + 1) / 2` can only pull it half a step away. The mean is floored, so the
+    /// true mean is somewhere up to 1 above it, and the best position is `mean` or `mean + 1`.
+    /// One more on each side costs two extra totals, and guards against rounding.
+    fn align(&self, crabs: &[u16]) -> Option<Alignment> {
+        let (&min, &max) = (crabs.iter().min()?, crabs.iter().max()?);
+        let sum = crabs.iter().map(|&crab| u64::from(crab)).sum::<u64>();
+        let mean = (sum / crabs.len() as u64) as u16;
+
+        (mean.saturating_sub(1)..=mean.saturating_add(2))
+            .map(|position| position.clamp(min, max))
+            .map(|position| Alignment {
+                position,
+                fuel: self.total(crabs, position),
+            })
+            .min_by_key(|alignment| (alignment.fuel, alignment.position))
+    }
+}
+
+/// Any convex cost which never decreases with distance, given as a function of the distance,
+/// aligned by ternary search. See [CostModel::align] for why both are needed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Convex<F>(pub F);
+
+impl<F: Fn(u64) -> u64> CostModel for Convex<F> {
+    #[inline]
+    fn cost(&self, distance: u64) -> u64 {
+        (self.0)(distance)
+    }
+}
+
+/// Finds a position with the least `total` between the outermost crabs.
+/// `total` must be convex.
+pub fn ternary_search(crabs: &[u16], total: impl Fn(u16) -> u64) -> Option<Alignment> {
+    let (mut lo, mut hi) = (*crabs.iter().min()?, *crabs.iter().max()?);
+
+    while hi - lo > 2 {
+        let third = (hi - lo) / 3;
+        let (a, b) = (lo + third, hi - third);
+        // being convex, an equal pair either brackets the optimum or sits on it
+        if total(a) <= total(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+
+    (lo..=hi)
+        .map(|position| Alignment {
+            position,
+            fuel: total(position),
+        })
+        .min_by_key(|alignment| (alignment.fuel, alignment.position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u16; 10] = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+    /// Tries every position between the outermost crabs.
+    fn brute_force(model: &impl CostModel, crabs: &[u16]) -> u64 {
+        let (&min, &max) = (crabs.iter().min().unwrap(), crabs.iter().max().unwrap());
+        (min..=max)
+            .map(|position| model.total(crabs, position))
+            .min()
+            .unwrap()
+    }
+
+    fn crabs() -> Vec<u16> {
+        parse(include_str!("../../input.txt")).unwrap()
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse("16,1,2\n"), Ok(vec![16, 1, 2]));
+        assert_eq!(
+            parse("1,65536,2"),
+            Err(ParseError::OutOfRange {
+                crab: 2,
+                pos: "65536".to_string()
+            })
+        );
+        assert_eq!(
+            parse("1,2,-3"),
+            Err(ParseError::NotAPosition {
+                crab: 3,
+                pos: "-3".to_string()
+            })
+        );
+        assert_eq!(
+            parse(""),
+            Err(ParseError::NotAPosition {
+                crab: 1,
+                pos: "".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(
+            Linear.align(&EXAMPLE),
+            Some(Alignment {
+                position: 2,
+                fuel: 37
+            })
+        );
+        assert_eq!(
+            Triangular.align(&EXAMPLE),
+            Some(Alignment {
+                position: 5,
+                fuel: 168
+            })
+        );
+        assert_eq!(Linear.total(&EXAMPLE, 10), 71);
+        assert_eq!(Triangular.total(&EXAMPLE, 2), 206);
+    }
+
+    #[test]
+    fn models_agree() {
+        let crabs = crabs();
+        let linear = Convex(|d| d);
+        let triangular = Convex(|d| d * (d + 1) / 2);
+        let cubic = Convex(|d: u64| d * d * d);
+
+        for crabs in [&EXAMPLE[..], &crabs] {
+            let fuel = brute_force(&Linear, crabs);
+            assert_eq!(Linear.align(crabs).unwrap().fuel, fuel);
+            assert_eq!(linear.align(crabs).unwrap().fuel, fuel);
+
+            let fuel = brute_force(&Triangular, crabs);
+            assert_eq!(Triangular.align(crabs).unwrap().fuel, fuel);
+            assert_eq!(triangular.align(crabs).unwrap().fuel, fuel);
+
+            assert_eq!(cubic.align(crabs).unwrap().fuel, brute_force(&cubic, crabs));
+        }
+    }
+
+    #[test]
+    fn edges() {
+        let single = Some(Alignment {
+            position: 9,
+            fuel: 0,
+        });
+        assert_eq!(Linear.align(&[9]), single);
+        assert_eq!(Triangular.align(&[9]), single);
+        assert_eq!(Convex(|d| d).align(&[9, 9, 9]), single);
+
+        assert_eq!(Linear.align(&[]), None);
+        assert_eq!(Triangular.align(&[]), None);
+        assert_eq!(Convex(|d| d).align(&[]), None);
+
+        // crabs at both ends of the range
+        let crabs = [0, u16::MAX, u16::MAX];
+        assert_eq!(
+            Triangular.align(&crabs).unwrap().fuel,
+            brute_force(&Triangular, &crabs)
+        );
+        assert_eq!(Linear.align(&crabs).unwrap().position, u16::MAX);
+    }
+}
@@ -0,0 +1,91 @@
+//! Ways of finding the median crab.
+//!
+//! Each returns the upper median, `sorted[len / 2]`, or `None` if there are no crabs.
+
+/// Every possible position, for counting.
+const POSITIONS: usize = u16::MAX as usize + 1;
+
+/// Below this many crabs, clearing and walking all [POSITIONS] counters costs more than
+/// [by_selection] does. Found with `cargo bench`.
+const COUNTING_THRESHOLD: usize = 1 << 14;
+
+/// Picks whichever of [by_selection] and [by_counting] should be quicker.
+pub fn median(crabs: &[u16]) -> Option<u16> {
+    if crabs.len() < COUNTING_THRESHOLD {
+        by_selection(crabs)
+    } else {
+        by_counting(crabs)
+    }
+}
+
+/// Sorts a copy of the crabs. O(n log n), and the reference the others are tested against.
+pub fn by_sorting(crabs: &[u16]) -> Option<u16> {
+    let mut sorted = crabs.to_vec();
+    sorted.sort_unstable();
+    sorted.get(crabs.len() / 2).copied()
+}
+
+/// Quickselects in a copy of the crabs. O(n) on average.
+pub fn by_selection(crabs: &[u16]) -> Option<u16> {
+    if crabs.is_empty() {
+        return None;
+    }
+    let mut crabs = crabs.to_vec();
+    let mid = crabs.len() / 2;
+    Some(*crabs.select_nth_unstable(mid).1)
+}
+
+/// Counts the crabs at each position, then walks the counts up to the middle crab.
+/// O(n), plus a constant 65536 positions however few crabs there are.
+pub fn by_counting(crabs: &[u16]) -> Option<u16> {
+    let mut counts = vec![0usize; POSITIONS];
+    for &crab in crabs {
+        counts[usize::from(crab)] += 1;
+    }
+
+    // the number of crabs at lower positions than the median
+    let mut below = crabs.len() / 2;
+    for (position, &count) in counts.iter().enumerate() {
+        if below < count {
+            return Some(position as u16);
+        }
+        below -= count;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_agree() {
+        let mut state = 7u32;
+        let random = (0..20_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u16
+            })
+            .collect::<Vec<_>>();
+
+        let inputs: [&[u16]; 6] = [
+            &[],
+            &[3],
+            &[5, 1],
+            &[16, 1, 2, 0, 4, 2, 7, 1, 2, 14],
+            &[u16::MAX, 0, u16::MAX],
+            &random,
+        ];
+        for crabs in inputs {
+            let expected = by_sorting(crabs);
+            assert_eq!(by_selection(crabs), expected);
+            assert_eq!(by_counting(crabs), expected);
+            assert_eq!(median(crabs), expected);
+        }
+
+        assert_eq!(by_counting(&[5, 1]), Some(5));
+        assert_eq!(by_counting(&[u16::MAX, 0, u16::MAX]), Some(u16::MAX));
+    }
+}
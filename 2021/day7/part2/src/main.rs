@@ -0,0 +1,12 @@
+use day7_part1::{parse, CostModel, Triangular};
+
+fn main() {
+    let input = include_str!("../../input.txt");
+
+    let crab_pos = parse(input).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    });
+
+    println!("{}", Triangular.align(&crab_pos).unwrap().fuel);
+}